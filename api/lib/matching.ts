@@ -5,18 +5,28 @@
 
 import { Persona } from './supabase';
 
+/**
+ * 置信度为 0 时信誉分向该中性值收缩
+ */
+const NEUTRAL_TRUST_SCORE = 50;
+
 /**
  * 计算两个用户的匹配分数
  * score = 0.5 * keywordSimilarity + 0.3 * riskMatch + 0.2 * trustScoreNorm
+ *
+ * trustConfidence 为链上 get_weighted_score_detailed 返回的 confidence_bps / 10000
+ * （见 lib/solana.ts 的 fetchOnChainWeightedScore），缺省视为 1（完全可信）
  */
 export function calculateMatchScore(
   userA: {
     persona: Persona;
     trustScore: number;
+    trustConfidence?: number;
   },
   userB: {
     persona: Persona;
     trustScore: number;
+    trustConfidence?: number;
   }
 ): number {
   // 1. 关键词相似度 (50% 权重)
@@ -31,10 +41,10 @@ export function calculateMatchScore(
     userB.persona.risk_type
   );
 
-  // 3. 信誉分归一化 (20% 权重)
+  // 3. 信誉分归一化 (20% 权重)，低置信度的新用户向中性值收缩
   const trustScoreNorm = normalizeTrustScores(
-    userA.trustScore,
-    userB.trustScore
+    discountTrustScore(userA.trustScore, userA.trustConfidence),
+    discountTrustScore(userB.trustScore, userB.trustConfidence)
   );
 
   // 加权计算最终分数
@@ -97,6 +107,14 @@ function normalizeTrustScores(scoreA: number, scoreB: number): number {
   return (diffPenalty * 0.6 + avgBonus * 0.4);
 }
 
+/**
+ * 按置信度折算信誉分：score' = neutral + (score - neutral) * confidence
+ */
+function discountTrustScore(score: number, confidence: number = 1): number {
+  const clamped = Math.min(Math.max(confidence, 0), 1);
+  return NEUTRAL_TRUST_SCORE + (score - NEUTRAL_TRUST_SCORE) * clamped;
+}
+
 /**
  * 获取推荐候选列表
  */
@@ -104,16 +122,19 @@ export function rankCandidates(
   currentUser: {
     persona: Persona;
     trustScore: number;
+    trustConfidence?: number;
   },
   candidates: Array<{
     id: string;
     persona: Persona;
     trustScore: number;
+    trustConfidence?: number;
   }>
 ): Array<{
   id: string;
   persona: Persona;
   trustScore: number;
+  trustConfidence?: number;
   matchScore: number;
 }> {
   return candidates
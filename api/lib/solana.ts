@@ -3,7 +3,7 @@
  * 真实的链上交互工具
  */
 
-import { Connection, PublicKey, Keypair, SystemProgram, Transaction, TransactionInstruction, sendAndConfirmTransaction } from '@solana/web3.js';
+import { Connection, PublicKey, Keypair, SystemProgram, Transaction, TransactionInstruction, TransactionMessage, VersionedTransaction, sendAndConfirmTransaction } from '@solana/web3.js';
 import { AnchorProvider, Program, Idl } from '@coral-xyz/anchor';
 import * as SolanaCLI from './solana-cli';
 import * as SolanaCLITx from './solana-cli-tx';
@@ -18,6 +18,11 @@ const RPC_URLS = [
   'https://devnet.helius-rpc.com',
 ];
 const TRUST_SCORE_PROGRAM_ID = process.env.TRUST_SCORE_PROGRAM_ID || '3FWDkwEPfVVZmxXS4f3pDaJpg4qf7GL5ir89DtXSwAjR';
+// 完整版 trust-score 程序（加权分与置信度）
+const FULL_TRUST_SCORE_PROGRAM_ID = process.env.FULL_TRUST_SCORE_PROGRAM_ID || '8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR2';
+
+// sha256("global:get_weighted_score_detailed") 前 8 字节
+const GET_WEIGHTED_SCORE_DETAILED_DISCRIMINATOR = Buffer.from([40, 68, 175, 138, 151, 226, 5, 212]);
 
 // SPL Memo Program ID（官方程序）
 const MEMO_PROGRAM_ID = new PublicKey('MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr');
@@ -412,6 +417,63 @@ export async function fetchOnChainTrustScore(userWallet: string): Promise<{
   }
 }
 
+/**
+ * 查询链上加权信誉分及置信度
+ * 模拟调用 get_weighted_score_detailed 并读取 return data，不发送交易
+ * 返回的 confidence 为 0-1，供匹配排序折算信誉分；账户不存在或查询失败时返回 null
+ */
+export async function fetchOnChainWeightedScore(userWallet: string): Promise<{
+  score: number;
+  confidence: number;
+} | null> {
+  try {
+    const programId = new PublicKey(FULL_TRUST_SCORE_PROGRAM_ID);
+    const userPubkey = new PublicKey(userWallet);
+    const [trustScorePDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from('trust_score'), userPubkey.toBuffer()],
+      programId
+    );
+    const [scoringConfigPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from('scoring_config')],
+      programId
+    );
+
+    const instruction = new TransactionInstruction({
+      programId,
+      keys: [
+        { pubkey: trustScorePDA, isSigner: false, isWritable: false },
+        { pubkey: userPubkey, isSigner: false, isWritable: false },
+        { pubkey: scoringConfigPDA, isSigner: false, isWritable: false },
+      ],
+      data: GET_WEIGHTED_SCORE_DETAILED_DISCRIMINATOR,
+    });
+
+    const { blockhash } = await connection.getLatestBlockhash('confirmed');
+    const message = new TransactionMessage({
+      payerKey: payerKeypair?.publicKey ?? userPubkey,
+      recentBlockhash: blockhash,
+      instructions: [instruction],
+    }).compileToV0Message();
+
+    const { value } = await connection.simulateTransaction(new VersionedTransaction(message), {
+      sigVerify: false,
+    });
+    if (value.err || !value.returnData) {
+      return null;
+    }
+
+    // WeightedScore { score: u16, confidence_bps: u16 }
+    const data = Buffer.from(value.returnData.data[0], 'base64');
+    return {
+      score: data.readUInt16LE(0),
+      confidence: data.readUInt16LE(2) / 10000,
+    };
+  } catch (error) {
+    console.error('❌ 查询链上加权信誉分失败:', error);
+    return null;
+  }
+}
+
 /**
  * 验证交易是否确认
  */
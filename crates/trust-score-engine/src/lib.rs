@@ -0,0 +1,699 @@
+/*!
+ * Trust Score Engine
+ * 信誉分计算引擎（no_std、定点数、纯函数）
+ *
+ * 链上 programs/trust-score 与链下服务共用同一套计算逻辑：
+ * 所有函数不读取时钟，当前时间 `now` 由调用方传入。
+ */
+
+#![no_std]
+
+/// 信誉分上限
+pub const MAX_SCORE: u16 = 1000;
+
+/// 基点单位（10000 = 1.0）
+pub const BPS: u64 = 10_000;
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Q32 定点数的 1.0
+const Q32_ONE: u64 = 1 << 32;
+
+/// 2^(-2^-k)，k = 1..=20，Q32 定点数
+const HALF_LIFE_ROOTS: [u64; 20] = [
+    3_037_000_500,
+    3_611_622_603,
+    3_938_502_376,
+    4_112_874_773,
+    4_202_935_003,
+    4_248_701_965,
+    4_271_771_996,
+    4_283_353_945,
+    4_289_156_690,
+    4_292_061_010,
+    4_293_513_907,
+    4_294_240_540,
+    4_294_603_903,
+    4_294_785_595,
+    4_294_876_445,
+    4_294_921_870,
+    4_294_944_583,
+    4_294_955_939,
+    4_294_961_618,
+    4_294_964_457,
+];
+
+// ==========================================
+// 数据结构
+// ==========================================
+
+/// 评分所需的账户状态快照
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub base_score: u16,
+    pub total_interactions: u32,
+    pub positive_interactions: u32,
+    pub reports_validated: u16,
+    pub learning_streak: u16,
+    pub last_active: i64,
+    pub last_decay_at: i64,
+    pub sub_scores: SubScores,
+    /// 账户创建时间，用于判断是否处于观察期
+    pub created_at: i64,
+}
+
+/// 分项信誉分所属维度
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    Social,
+    Learning,
+    Mentoring,
+    Safety,
+}
+
+impl Category {
+    pub const ALL: [Category; 4] = [
+        Category::Social,
+        Category::Learning,
+        Category::Mentoring,
+        Category::Safety,
+    ];
+}
+
+/// 分项信誉分：社交、学习、指导、安全，范围同 base_score
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SubScores {
+    pub social: u16,
+    pub learning: u16,
+    pub mentoring: u16,
+    pub safety: u16,
+}
+
+impl SubScores {
+    /// 各分项取同一初始值（新账户或旧账户迁移时使用）
+    pub fn uniform(score: u16) -> Self {
+        Self {
+            social: score,
+            learning: score,
+            mentoring: score,
+            safety: score,
+        }
+    }
+
+    pub fn get(&self, category: Category) -> u16 {
+        match category {
+            Category::Social => self.social,
+            Category::Learning => self.learning,
+            Category::Mentoring => self.mentoring,
+            Category::Safety => self.safety,
+        }
+    }
+
+    fn get_mut(&mut self, category: Category) -> &mut u16 {
+        match category {
+            Category::Social => &mut self.social,
+            Category::Learning => &mut self.learning,
+            Category::Mentoring => &mut self.mentoring,
+            Category::Safety => &mut self.safety,
+        }
+    }
+
+    /// 将 base_score 的变化量同步到对应分项
+    pub fn shift(&mut self, category: Category, old_score: u16, new_score: u16) {
+        let value = self.get_mut(category);
+        *value = clamp_score(*value as i64 + new_score as i64 - old_score as i64);
+    }
+
+    /// 将 base_score 的变化量同步到所有分项（无法归因到单一维度的变化，如纪元结算）
+    pub fn shift_all(&mut self, old_score: u16, new_score: u16) {
+        for category in Category::ALL {
+            self.shift(category, old_score, new_score);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteractionKind {
+    Match,
+    Chat,
+    HelpfulResponse,
+    SharedContent,
+    NoShow,
+    Spam,
+    Harassment,
+    Scam,
+}
+
+impl InteractionKind {
+    /// 负面行为（需由证明方记录）
+    pub fn is_negative(&self) -> bool {
+        matches!(
+            self,
+            InteractionKind::NoShow
+                | InteractionKind::Spam
+                | InteractionKind::Harassment
+                | InteractionKind::Scam
+        )
+    }
+
+    /// 互动计入的分项：匹配与聊天计入社交，答疑与分享计入指导，负面行为计入安全
+    pub fn category(&self) -> Category {
+        match self {
+            InteractionKind::Match | InteractionKind::Chat => Category::Social,
+            InteractionKind::HelpfulResponse | InteractionKind::SharedContent => Category::Mentoring,
+            _ => Category::Safety,
+        }
+    }
+}
+
+/// 正面互动率的估计方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScoringMode {
+    /// 原始比例 positive / total
+    #[default]
+    Classic,
+    /// Wilson 置信下界，样本少时更保守
+    Wilson,
+}
+
+/// 加权信誉分及其置信度
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScoreEstimate {
+    pub score: u16,
+    /// 置信度（基点）= 1 - Wilson 区间宽度
+    pub confidence_bps: u16,
+}
+
+/// 评分参数，含义与链上 `ScoringParams` 一一对应
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Params {
+    pub match_weight: u8,
+    pub chat_weight: u8,
+    pub helpful_response_weight: u8,
+    pub shared_content_weight: u8,
+    pub positive_threshold: u8,
+    pub positive_rate_bonus_threshold: u8,
+    pub positive_rate_bonus: u16,
+    pub streak_bonus_days: u16,
+    pub streak_bonus_points: u16,
+    pub learning_engagement_threshold: u8,
+    pub learning_bonus: u16,
+    pub report_penalty: u16,
+    pub validation_penalty: u16,
+    pub active_days: u16,
+    pub active_multiplier_bps: u16,
+    pub inactive_days: u16,
+    pub inactive_multiplier_bps: u16,
+    pub quality_min_interactions: u32,
+    pub quality_base_bps: u16,
+    pub quality_range_bps: u16,
+    /// 不活跃衰减半衰期（天，0 表示关闭）
+    pub decay_half_life_days: u16,
+    /// 衰减下限，低于该值的分数不再衰减
+    pub decay_floor: u16,
+    /// 担保权重 = 担保人 base_score × vouch_weight_bps，上限 max_vouch_weight
+    pub vouch_weight_bps: u16,
+    pub max_vouch_weight: u16,
+    /// 担保人最低分数与同时有效的担保数上限
+    pub min_voucher_score: u16,
+    pub max_outstanding_vouches: u16,
+    /// 被担保人举报成立时，每个担保人的扣分
+    pub voucher_penalty: u16,
+    /// 同一对方的计数窗口（秒）
+    pub pair_window_seconds: u32,
+    /// 窗口内不打折的互动次数
+    pub pair_free_interactions: u16,
+    /// 超出后每次互动的加分再乘以该系数（基点）
+    pub pair_decay_bps: u16,
+    /// 负面行为的扣分（按严重程度）
+    pub no_show_severity: u16,
+    pub spam_severity: u16,
+    pub harassment_severity: u16,
+    pub scam_severity: u16,
+    pub scoring_mode: ScoringMode,
+    /// Wilson 区间的 z 值 × 1000（1960 ≈ 95% 置信）
+    pub wilson_z_milli: u16,
+    /// 加权分中各分项的组合权重（基点），全为 0 时直接使用 base_score
+    pub social_weight_bps: u16,
+    pub learning_weight_bps: u16,
+    pub mentoring_weight_bps: u16,
+    pub safety_weight_bps: u16,
+    /// 新账户观察期（秒，0 表示关闭）
+    pub probation_seconds: u32,
+    /// 观察期内加分后的分数上限
+    pub probation_score_cap: u16,
+    /// 观察期内举报扣分的倍数（基点，不低于 10000）
+    pub probation_report_multiplier_bps: u16,
+    /// 评分人加权分达到该值时评分按全额计入（0 表示不按评分人缩放）
+    pub rater_full_weight_score: u16,
+    /// 评分人权重下限（基点），新用户的评分仍有少量影响
+    pub rater_floor_bps: u16,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            match_weight: 2,
+            chat_weight: 3,
+            helpful_response_weight: 5,
+            shared_content_weight: 4,
+            positive_threshold: 60,
+            positive_rate_bonus_threshold: 80,
+            positive_rate_bonus: 20,
+            streak_bonus_days: 7,
+            streak_bonus_points: 10,
+            learning_engagement_threshold: 70,
+            learning_bonus: 5,
+            report_penalty: 25,
+            validation_penalty: 50,
+            active_days: 7,
+            active_multiplier_bps: 11_000,
+            inactive_days: 30,
+            inactive_multiplier_bps: 8_000,
+            quality_min_interactions: 10,
+            quality_base_bps: 8_000,
+            quality_range_bps: 4_000,
+            decay_half_life_days: 90,
+            decay_floor: 100,
+            vouch_weight_bps: 500,
+            max_vouch_weight: 50,
+            min_voucher_score: 300,
+            max_outstanding_vouches: 5,
+            voucher_penalty: 20,
+            pair_window_seconds: 86_400,
+            pair_free_interactions: 3,
+            pair_decay_bps: 5_000,
+            no_show_severity: 10,
+            spam_severity: 20,
+            harassment_severity: 50,
+            scam_severity: 100,
+            scoring_mode: ScoringMode::Classic,
+            wilson_z_milli: 1_960,
+            social_weight_bps: 0,
+            learning_weight_bps: 0,
+            mentoring_weight_bps: 0,
+            safety_weight_bps: 0,
+            probation_seconds: 7 * 86_400,
+            probation_score_cap: 300,
+            probation_report_multiplier_bps: 20_000,
+            rater_full_weight_score: 500,
+            rater_floor_bps: 2_000,
+        }
+    }
+}
+
+impl Params {
+    /// 正面互动的质量分权重（负面行为为 0）
+    pub fn weight(&self, kind: InteractionKind) -> u8 {
+        match kind {
+            InteractionKind::Match => self.match_weight,
+            InteractionKind::Chat => self.chat_weight,
+            InteractionKind::HelpfulResponse => self.helpful_response_weight,
+            InteractionKind::SharedContent => self.shared_content_weight,
+            _ => 0,
+        }
+    }
+
+    /// 负面行为的扣分（正面互动为 0）
+    pub fn severity(&self, kind: InteractionKind) -> u16 {
+        match kind {
+            InteractionKind::NoShow => self.no_show_severity,
+            InteractionKind::Spam => self.spam_severity,
+            InteractionKind::Harassment => self.harassment_severity,
+            InteractionKind::Scam => self.scam_severity,
+            _ => 0,
+        }
+    }
+
+    /// 分项组合权重（基点）
+    pub fn category_weight_bps(&self, category: Category) -> u16 {
+        match category {
+            Category::Social => self.social_weight_bps,
+            Category::Learning => self.learning_weight_bps,
+            Category::Mentoring => self.mentoring_weight_bps,
+            Category::Safety => self.safety_weight_bps,
+        }
+    }
+}
+
+// ==========================================
+// 评分函数
+// ==========================================
+
+/// 是否计为正面互动
+pub fn is_positive(params: &Params, kind: InteractionKind, quality_score: u8) -> bool {
+    !kind.is_negative() && quality_score >= params.positive_threshold
+}
+
+/// 新账户是否仍处于观察期
+pub fn in_probation(stats: &Stats, params: &Params, now: i64) -> bool {
+    now.saturating_sub(stats.created_at) < params.probation_seconds as i64
+}
+
+/// 观察期内加分后的分数不超过 probation_score_cap（已高于上限的分数不变）
+pub fn cap_probation_gain(stats: &Stats, params: &Params, new_score: u16, now: i64) -> u16 {
+    if new_score <= stats.base_score || !in_probation(stats, params, now) {
+        return new_score;
+    }
+    new_score.min(stats.base_score.max(params.probation_score_cap))
+}
+
+/// 举报扣分的倍数（基点），观察期内加重
+fn report_multiplier_bps(stats: &Stats, params: &Params, now: i64) -> i64 {
+    if in_probation(stats, params, now) {
+        params.probation_report_multiplier_bps.max(BPS as u16) as i64
+    } else {
+        BPS as i64
+    }
+}
+
+/// 记录一次互动后的新 base_score
+/// `stats` 应为已累加本次互动计数后的状态
+pub fn interaction_score(
+    stats: &Stats,
+    params: &Params,
+    kind: InteractionKind,
+    quality_score: u8,
+    now: i64,
+) -> u16 {
+    // 负面行为直接按严重程度扣分，不参与各项加成
+    if kind.is_negative() {
+        return stats.base_score.saturating_sub(params.severity(kind));
+    }
+
+    let mut score = stats.base_score as i64;
+
+    // 根据质量评分与互动类型权重调整
+    score += ((quality_score as i64 - 50) * params.weight(kind) as i64) / 10;
+
+    // 正面互动率加成
+    if stats.total_interactions > 0 {
+        let positive_rate = positive_rate_bps(stats, params) / 100;
+        if positive_rate > params.positive_rate_bonus_threshold as u64 {
+            score += params.positive_rate_bonus as i64;
+        }
+    }
+
+    // 学习连胜加成
+    if params.streak_bonus_days > 0 && stats.learning_streak > params.streak_bonus_days {
+        score += (stats.learning_streak / params.streak_bonus_days) as i64
+            * params.streak_bonus_points as i64;
+    }
+
+    // 举报惩罚（仅计入审核通过的举报，观察期内加重）
+    score -= stats.reports_validated as i64 * params.report_penalty as i64
+        * report_multiplier_bps(stats, params, now)
+        / BPS as i64;
+
+    cap_probation_gain(stats, params, clamp_score(score), now)
+}
+
+/// 学习连胜：距上次活跃不足一天则 +1，否则重置为 1
+pub fn next_learning_streak(stats: &Stats, now: i64) -> u16 {
+    if now - stats.last_active < SECONDS_PER_DAY {
+        stats.learning_streak.saturating_add(1)
+    } else {
+        1
+    }
+}
+
+/// 记录学习活动后的新 base_score
+/// `stats.learning_streak` 应为已更新后的连胜天数
+pub fn learning_score(stats: &Stats, params: &Params, engagement_score: u8, now: i64) -> u16 {
+    if engagement_score > params.learning_engagement_threshold
+        && stats.learning_streak > params.streak_bonus_days
+    {
+        let new_score = stats
+            .base_score
+            .saturating_add(params.learning_bonus)
+            .min(MAX_SCORE);
+        cap_probation_gain(stats, params, new_score, now)
+    } else {
+        stats.base_score
+    }
+}
+
+/// 举报审核通过后的新 base_score（观察期内扣分加重）
+pub fn validated_report_score(stats: &Stats, params: &Params, now: i64) -> u16 {
+    let penalty = params.validation_penalty as i64 * report_multiplier_bps(stats, params, now)
+        / BPS as i64;
+    clamp_score(stats.base_score as i64 - penalty)
+}
+
+/// 同一对方的滚动窗口：窗口过期则重新计数
+/// 返回 (窗口起点, 窗口内已有互动次数)
+pub fn roll_pair_window(window_start: i64, window_count: u16, params: &Params, now: i64) -> (i64, u16) {
+    if now - window_start >= params.pair_window_seconds as i64 {
+        (now, 0)
+    } else {
+        (window_start, window_count)
+    }
+}
+
+/// 窗口内已有 `window_count` 次互动时，本次加分的系数（基点）
+/// 即 pair_decay_bps ^ (window_count - pair_free_interactions + 1)，按平方求幂计算
+pub fn pair_multiplier_bps(window_count: u16, params: &Params) -> u64 {
+    if window_count < params.pair_free_interactions {
+        return BPS;
+    }
+    let decay = (params.pair_decay_bps as u64).min(BPS);
+    if decay == BPS {
+        return BPS;
+    }
+
+    let mut exponent = (window_count - params.pair_free_interactions) as u32 + 1;
+    let mut base = decay;
+    let mut multiplier = BPS;
+    while exponent > 0 && multiplier > 0 {
+        if exponent & 1 == 1 {
+            multiplier = multiplier * base / BPS;
+        }
+        base = base * base / BPS;
+        exponent >>= 1;
+    }
+    multiplier
+}
+
+/// 只对加分部分按系数打折，扣分保持不变
+pub fn scale_gain(old_score: u16, new_score: u16, multiplier_bps: u64) -> u16 {
+    if new_score <= old_score {
+        return new_score;
+    }
+    let gain = (new_score - old_score) as u64 * multiplier_bps.min(BPS) / BPS;
+    old_score + gain as u16
+}
+
+/// 按系数缩放分数变化（加分与扣分都缩放）
+pub fn scale_adjustment(old_score: u16, new_score: u16, multiplier_bps: u64) -> u16 {
+    let delta = new_score as i64 - old_score as i64;
+    clamp_score(old_score as i64 + delta * multiplier_bps.min(BPS) as i64 / BPS as i64)
+}
+
+/// 评分人加权分对应的评分权重（基点），介于 rater_floor_bps 与 10000 之间
+pub fn rater_weight_bps(rater_score: u16, params: &Params) -> u64 {
+    if params.rater_full_weight_score == 0 {
+        return BPS;
+    }
+    (rater_score as u64 * BPS / params.rater_full_weight_score as u64)
+        .max(params.rater_floor_bps as u64)
+        .min(BPS)
+}
+
+/// 没有评分人（无对方或对方尚未创建 TrustScore）时的评分权重，按 rater_floor_bps 计入
+pub fn unrated_weight_bps(params: &Params) -> u64 {
+    rater_weight_bps(0, params)
+}
+
+/// 担保人当前分数对应的担保权重
+pub fn vouch_weight(voucher_score: u16, params: &Params) -> u16 {
+    let weight = voucher_score as u64 * params.vouch_weight_bps as u64 / BPS;
+    weight.min(params.max_vouch_weight as u64) as u16
+}
+
+/// 加权信誉分及置信度
+pub fn weighted_estimate(stats: &Stats, params: &Params, now: i64) -> ScoreEstimate {
+    let (_, width_bps) = wilson_interval_bps(
+        stats.positive_interactions.min(stats.total_interactions) as u64,
+        stats.total_interactions as u64,
+        params.wilson_z_milli as u64,
+    );
+
+    ScoreEstimate {
+        score: weighted_score(stats, params, now),
+        confidence_bps: (BPS - width_bps.min(BPS)) as u16,
+    }
+}
+
+/// 按组合权重合成的基础分；未配置权重时为 base_score
+pub fn composite_score(stats: &Stats, params: &Params) -> u16 {
+    let categories = [
+        Category::Social,
+        Category::Learning,
+        Category::Mentoring,
+        Category::Safety,
+    ];
+
+    let total_weight: u64 = categories
+        .iter()
+        .map(|&category| params.category_weight_bps(category) as u64)
+        .sum();
+    if total_weight == 0 {
+        return stats.base_score;
+    }
+
+    let weighted: u64 = categories
+        .iter()
+        .map(|&category| {
+            stats.sub_scores.get(category) as u64 * params.category_weight_bps(category) as u64
+        })
+        .sum();
+    (weighted / total_weight).min(MAX_SCORE as u64) as u16
+}
+
+/// 加权信誉分：组合基础分 × 活跃度乘数 × 互动质量乘数（全部为基点整数运算）
+pub fn weighted_score(stats: &Stats, params: &Params, now: i64) -> u16 {
+    let mut weighted = composite_score(stats, params) as u64 * BPS;
+
+    // 活跃度权重
+    let days_inactive = (now - stats.last_active) / SECONDS_PER_DAY;
+    if days_inactive < params.active_days as i64 {
+        weighted = weighted * params.active_multiplier_bps as u64 / BPS;
+    } else if days_inactive > params.inactive_days as i64 {
+        weighted = weighted * params.inactive_multiplier_bps as u64 / BPS;
+    }
+
+    // 互动质量权重
+    if stats.total_interactions > params.quality_min_interactions {
+        let quality_bps = params.quality_base_bps as u64
+            + positive_rate_bps(stats, params) * params.quality_range_bps as u64 / BPS;
+        weighted = weighted * quality_bps / BPS;
+    }
+
+    (weighted / BPS).min(MAX_SCORE as u64) as u16
+}
+
+/// 不活跃衰减后的 base_score
+/// 从 max(last_active, last_decay_at) 起按半衰期向 decay_floor 衰减，剩余的超出部分向上取整
+pub fn decayed_score(stats: &Stats, params: &Params, now: i64) -> u16 {
+    decay_toward_floor(stats.base_score, stats, params, now)
+}
+
+/// 衰减 crank 的一步：base_score 实际下降时返回 (新分数, 新分项)，否则返回 None
+/// 调用方只在返回 Some 时把 last_decay_at 推进到 now，
+/// 因此反复调用既不会比连续衰减更快，也不会重置计时冻结衰减
+pub fn decay_step(stats: &Stats, params: &Params, now: i64) -> Option<(u16, SubScores)> {
+    let new_score = decayed_score(stats, params, now);
+    if new_score >= stats.base_score {
+        return None;
+    }
+    Some((new_score, decayed_sub_scores(stats, params, now)))
+}
+
+/// 不活跃衰减后的分项信誉分，与 base_score 使用同一半衰期和下限
+pub fn decayed_sub_scores(stats: &Stats, params: &Params, now: i64) -> SubScores {
+    let sub_scores = &stats.sub_scores;
+    SubScores {
+        social: decay_toward_floor(sub_scores.social, stats, params, now),
+        learning: decay_toward_floor(sub_scores.learning, stats, params, now),
+        mentoring: decay_toward_floor(sub_scores.mentoring, stats, params, now),
+        safety: decay_toward_floor(sub_scores.safety, stats, params, now),
+    }
+}
+
+fn decay_toward_floor(score: u16, stats: &Stats, params: &Params, now: i64) -> u16 {
+    if params.decay_half_life_days == 0 || score <= params.decay_floor {
+        return score;
+    }
+
+    let since = stats.last_active.max(stats.last_decay_at);
+    let elapsed = now.saturating_sub(since);
+    if elapsed <= 0 {
+        return score;
+    }
+
+    let half_life = params.decay_half_life_days as u64 * SECONDS_PER_DAY as u64;
+    let factor = half_life_factor(elapsed as u64, half_life);
+    let excess = (score - params.decay_floor) as u64;
+
+    params.decay_floor + (excess * factor).div_ceil(Q32_ONE) as u16
+}
+
+/// 2^(-elapsed / half_life)，Q32 定点数
+fn half_life_factor(elapsed: u64, half_life: u64) -> u64 {
+    let halvings = elapsed / half_life;
+    if halvings >= 32 {
+        return 0;
+    }
+
+    // 小数部分按二进制位逐位乘上 2^(-2^-k)
+    let fraction = (((elapsed % half_life) as u128) << 32) / half_life as u128;
+    let mut factor = Q32_ONE;
+    for (k, root) in HALF_LIFE_ROOTS.iter().enumerate() {
+        if fraction & (1 << (31 - k)) != 0 {
+            factor = (factor * root) >> 32;
+        }
+    }
+
+    factor >> halvings
+}
+
+/// 正面互动率（基点），按评分模式取原始比例或 Wilson 下界
+pub fn positive_rate_bps(stats: &Stats, params: &Params) -> u64 {
+    let positive = stats.positive_interactions.min(stats.total_interactions) as u64;
+    let total = stats.total_interactions as u64;
+    if total == 0 {
+        return 0;
+    }
+
+    match params.scoring_mode {
+        ScoringMode::Classic => positive * BPS / total,
+        ScoringMode::Wilson => wilson_interval_bps(positive, total, params.wilson_z_milli as u64).0,
+    }
+}
+
+/// Wilson 置信区间，返回 (下界, 区间宽度)，均为基点
+///
+/// 下界 = (k + z²/2 - z·sqrt(k(n-k)/n + z²/4)) / (n + z²)
+/// 全部按 1e6 放大后做整数运算
+pub fn wilson_interval_bps(positive: u64, total: u64, z_milli: u64) -> (u64, u64) {
+    if total == 0 || z_milli == 0 {
+        let rate = (positive * BPS).checked_div(total).unwrap_or(0);
+        return (rate, if total == 0 { BPS } else { 0 });
+    }
+
+    const SCALE: u128 = 1_000_000;
+    let k = positive.min(total) as u128;
+    let n = total as u128;
+    let z = z_milli as u128;
+    let z_sq = z * z; // z² × 1e6
+
+    // z · sqrt(k(n-k)/n + z²/4)，× 1e6
+    let radicand = SCALE * k * (n - k) / n + z_sq / 4;
+    let spread = z * isqrt(radicand * SCALE) / 1_000;
+
+    let center = k * SCALE + z_sq / 2;
+    let denominator = n * SCALE + z_sq;
+
+    let lower = center.saturating_sub(spread) * BPS as u128 / denominator;
+    let width = 2 * spread * BPS as u128 / denominator;
+
+    (lower as u64, width.min(BPS as u128) as u64)
+}
+
+fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+fn clamp_score(score: i64) -> u16 {
+    score.clamp(0, MAX_SCORE as i64) as u16
+}
@@ -0,0 +1,445 @@
+/**
+ * 评分引擎属性测试
+ * 单调性与 0-1000 范围约束
+ */
+use proptest::prelude::*;
+use trust_score_engine::*;
+
+const NOW: i64 = 1_700_000_000;
+
+fn kind_strategy() -> impl Strategy<Value = InteractionKind> {
+    prop_oneof![
+        Just(InteractionKind::Match),
+        Just(InteractionKind::Chat),
+        Just(InteractionKind::HelpfulResponse),
+        Just(InteractionKind::SharedContent),
+        Just(InteractionKind::NoShow),
+        Just(InteractionKind::Spam),
+        Just(InteractionKind::Harassment),
+        Just(InteractionKind::Scam),
+    ]
+}
+
+fn stats_strategy() -> impl Strategy<Value = Stats> {
+    (
+        0u16..=MAX_SCORE,
+        0u32..10_000,
+        0u32..10_000,
+        0u16..100,
+        0u16..400,
+        0i64..(365 * SECONDS_PER_DAY),
+        sub_scores_strategy(),
+        0i64..(30 * SECONDS_PER_DAY),
+    )
+        .prop_map(|(base_score, total, positive, reports, streak, ago, sub_scores, age)| Stats {
+            base_score,
+            total_interactions: total.max(positive),
+            positive_interactions: positive,
+            reports_validated: reports,
+            learning_streak: streak,
+            last_active: NOW - ago,
+            last_decay_at: 0,
+            sub_scores,
+            created_at: NOW - age,
+        })
+}
+
+fn sub_scores_strategy() -> impl Strategy<Value = SubScores> {
+    (0u16..=MAX_SCORE, 0u16..=MAX_SCORE, 0u16..=MAX_SCORE, 0u16..=MAX_SCORE).prop_map(
+        |(social, learning, mentoring, safety)| SubScores {
+            social,
+            learning,
+            mentoring,
+            safety,
+        },
+    )
+}
+
+proptest! {
+    #[test]
+    fn interaction_score_is_clamped(
+        stats in stats_strategy(),
+        kind in kind_strategy(),
+        quality in 0u8..=100,
+    ) {
+        prop_assert!(interaction_score(&stats, &Params::default(), kind, quality, NOW) <= MAX_SCORE);
+    }
+
+    #[test]
+    fn weighted_score_is_clamped(stats in stats_strategy(), now_offset in 0i64..(365 * SECONDS_PER_DAY)) {
+        prop_assert!(weighted_score(&stats, &Params::default(), NOW + now_offset) <= MAX_SCORE);
+    }
+
+    #[test]
+    fn interaction_score_monotonic_in_quality(
+        stats in stats_strategy(),
+        kind in kind_strategy(),
+        quality in 0u8..100,
+    ) {
+        let params = Params::default();
+        prop_assert!(
+            interaction_score(&stats, &params, kind, quality, NOW)
+                <= interaction_score(&stats, &params, kind, quality + 1, NOW)
+        );
+    }
+
+    #[test]
+    fn interaction_score_monotonic_in_reports(
+        stats in stats_strategy(),
+        kind in kind_strategy(),
+        quality in 0u8..=100,
+    ) {
+        let params = Params::default();
+        let reported = Stats { reports_validated: stats.reports_validated + 1, ..stats };
+        prop_assert!(
+            interaction_score(&reported, &params, kind, quality, NOW)
+                <= interaction_score(&stats, &params, kind, quality, NOW)
+        );
+    }
+
+    #[test]
+    fn weighted_score_monotonic_in_base_score(stats in stats_strategy()) {
+        prop_assume!(stats.base_score < MAX_SCORE);
+        let params = Params::default();
+        let higher = Stats { base_score: stats.base_score + 1, ..stats };
+        prop_assert!(weighted_score(&stats, &params, NOW) <= weighted_score(&higher, &params, NOW));
+    }
+
+    #[test]
+    fn weighted_score_monotonic_in_positive_interactions(stats in stats_strategy()) {
+        prop_assume!(stats.positive_interactions < stats.total_interactions);
+        let params = Params::default();
+        let better = Stats { positive_interactions: stats.positive_interactions + 1, ..stats };
+        prop_assert!(weighted_score(&stats, &params, NOW) <= weighted_score(&better, &params, NOW));
+    }
+
+    #[test]
+    fn weighted_score_non_increasing_with_inactivity(
+        stats in stats_strategy(),
+        elapsed in 0i64..(365 * SECONDS_PER_DAY),
+    ) {
+        let params = Params::default();
+        prop_assert!(
+            weighted_score(&stats, &params, NOW + elapsed)
+                <= weighted_score(&stats, &params, NOW)
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn decay_never_increases_score(stats in stats_strategy(), elapsed in 0i64..(10 * 365 * SECONDS_PER_DAY)) {
+        prop_assert!(decayed_score(&stats, &Params::default(), NOW + elapsed) <= stats.base_score);
+    }
+
+    #[test]
+    fn decay_respects_floor(stats in stats_strategy(), elapsed in 0i64..(10 * 365 * SECONDS_PER_DAY)) {
+        let params = Params::default();
+        prop_assume!(stats.base_score >= params.decay_floor);
+        prop_assert!(decayed_score(&stats, &params, NOW + elapsed) >= params.decay_floor);
+    }
+
+    #[test]
+    fn repeated_decay_calls_never_beat_a_single_call(
+        stats in stats_strategy(),
+        interval in 1i64..(2 * SECONDS_PER_DAY),
+        calls in 1i64..200,
+    ) {
+        let params = Params::default();
+        let start = stats.last_active.max(stats.last_decay_at);
+        let end = start + interval * calls;
+        let cranked = crank(stats, &params, start, end, interval);
+        prop_assert!(cranked.base_score >= decayed_score(&stats, &params, end));
+    }
+
+    #[test]
+    fn decay_monotonic_in_elapsed(stats in stats_strategy(), elapsed in 0i64..(10 * 365 * SECONDS_PER_DAY)) {
+        let params = Params::default();
+        prop_assert!(
+            decayed_score(&stats, &params, NOW + elapsed + SECONDS_PER_DAY)
+                <= decayed_score(&stats, &params, NOW + elapsed)
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn vouch_weight_monotonic_and_capped(voucher_score in 0u16..MAX_SCORE) {
+        let params = Params::default();
+        let weight = vouch_weight(voucher_score, &params);
+        prop_assert!(weight <= params.max_vouch_weight);
+        prop_assert!(weight <= vouch_weight(voucher_score + 1, &params));
+    }
+}
+
+proptest! {
+    #[test]
+    fn pair_multiplier_non_increasing(count in 0u16..200) {
+        let params = Params::default();
+        prop_assert!(pair_multiplier_bps(count + 1, &params) <= pair_multiplier_bps(count, &params));
+        prop_assert!(pair_multiplier_bps(count, &params) <= BPS);
+    }
+
+    #[test]
+    fn pair_multiplier_non_increasing_for_any_decay(count in 0u16..=u16::MAX - 1, free in 0u16..10, decay in 0u16..=10_000) {
+        let params = Params { pair_free_interactions: free, pair_decay_bps: decay, ..Params::default() };
+        prop_assert!(pair_multiplier_bps(count + 1, &params) <= pair_multiplier_bps(count, &params));
+        prop_assert!(pair_multiplier_bps(count, &params) <= BPS);
+    }
+
+    #[test]
+    fn scaled_adjustment_stays_between_scores(old in 0u16..=MAX_SCORE, new in 0u16..=MAX_SCORE, multiplier in 0u64..=BPS) {
+        let scaled = scale_adjustment(old, new, multiplier);
+        prop_assert!(scaled >= old.min(new) && scaled <= old.max(new));
+    }
+
+    #[test]
+    fn rater_weight_monotonic_and_floored(rater_score in 0u16..MAX_SCORE) {
+        let params = Params::default();
+        let weight = rater_weight_bps(rater_score, &params);
+        prop_assert!(weight >= params.rater_floor_bps as u64 && weight <= BPS);
+        prop_assert!(weight <= rater_weight_bps(rater_score + 1, &params));
+    }
+
+    #[test]
+    fn scaled_gain_stays_between_scores(old in 0u16..=MAX_SCORE, new in 0u16..=MAX_SCORE, multiplier in 0u64..=BPS) {
+        let scaled = scale_gain(old, new, multiplier);
+        prop_assert!(scaled >= old.min(new) && scaled <= old.max(new));
+    }
+}
+
+proptest! {
+    #[test]
+    fn negative_interactions_never_raise_score(stats in stats_strategy(), kind in kind_strategy(), quality in 0u8..=100) {
+        prop_assume!(kind.is_negative());
+        let params = Params::default();
+        prop_assert!(interaction_score(&stats, &params, kind, quality, NOW) <= stats.base_score);
+        prop_assert!(!is_positive(&params, kind, quality));
+    }
+}
+
+proptest! {
+    #[test]
+    fn wilson_lower_bound_below_raw_rate(total in 1u64..100_000, positive in 0u64..100_000) {
+        let positive = positive.min(total);
+        let (lower, width) = wilson_interval_bps(positive, total, 1_960);
+        prop_assert!(lower <= positive * BPS / total);
+        prop_assert!(width <= BPS);
+    }
+
+    #[test]
+    fn wilson_lower_bound_grows_with_evidence(total in 1u64..10_000) {
+        // 同样 100% 正面，样本越多下界越高
+        let (smaller, _) = wilson_interval_bps(total, total, 1_960);
+        let (larger, _) = wilson_interval_bps(total + 1, total + 1, 1_960);
+        prop_assert!(smaller <= larger);
+    }
+
+    #[test]
+    fn weighted_estimate_is_clamped(stats in stats_strategy()) {
+        let params = Params { scoring_mode: ScoringMode::Wilson, ..Params::default() };
+        let estimate = weighted_estimate(&stats, &params, NOW);
+        prop_assert!(estimate.score <= MAX_SCORE);
+        prop_assert!(estimate.confidence_bps as u64 <= BPS);
+    }
+}
+
+proptest! {
+    #[test]
+    fn composite_score_within_sub_score_range(
+        stats in stats_strategy(),
+        weights in (0u16..10_000, 0u16..10_000, 0u16..10_000, 1u16..10_000),
+    ) {
+        let params = Params {
+            social_weight_bps: weights.0,
+            learning_weight_bps: weights.1,
+            mentoring_weight_bps: weights.2,
+            safety_weight_bps: weights.3,
+            ..Params::default()
+        };
+        let s = stats.sub_scores;
+        let low = s.social.min(s.learning).min(s.mentoring).min(s.safety);
+        let high = s.social.max(s.learning).max(s.mentoring).max(s.safety);
+        let composite = composite_score(&stats, &params);
+        prop_assert!(low <= composite && composite <= high);
+    }
+
+    #[test]
+    fn sub_score_shift_is_clamped(
+        sub_scores in sub_scores_strategy(),
+        old in 0u16..=MAX_SCORE,
+        new in 0u16..=MAX_SCORE,
+    ) {
+        let mut shifted = sub_scores;
+        shifted.shift(Category::Safety, old, new);
+        prop_assert!(shifted.safety <= MAX_SCORE);
+        prop_assert_eq!(shifted.social, sub_scores.social);
+    }
+
+    #[test]
+    fn uniform_sub_scores_follow_shift_all(score in 0u16..=MAX_SCORE, new in 0u16..=MAX_SCORE) {
+        let mut shifted = SubScores::uniform(score);
+        shifted.shift_all(score, new);
+        prop_assert_eq!(shifted, SubScores::uniform(new));
+    }
+
+    #[test]
+    fn sub_scores_decay_like_base(stats in stats_strategy(), elapsed_days in 0i64..3650) {
+        let stats = Stats { sub_scores: SubScores::uniform(stats.base_score), ..stats };
+        let params = Params::default();
+        let now = stats.last_active + elapsed_days * SECONDS_PER_DAY;
+        let decayed = decayed_sub_scores(&stats, &params, now);
+        prop_assert_eq!(decayed, SubScores::uniform(decayed_score(&stats, &params, now)));
+    }
+}
+
+proptest! {
+    #[test]
+    fn probation_caps_gains(stats in stats_strategy(), kind in kind_strategy(), quality in 0u8..=100) {
+        let params = Params::default();
+        let fresh = Stats { created_at: NOW, ..stats };
+        let score = interaction_score(&fresh, &params, kind, quality, NOW);
+        prop_assert!(score <= fresh.base_score.max(params.probation_score_cap));
+    }
+
+    #[test]
+    fn probation_reports_weigh_more(stats in stats_strategy()) {
+        let params = Params::default();
+        let fresh = Stats { created_at: NOW, ..stats };
+        let settled = Stats { created_at: NOW - 365 * SECONDS_PER_DAY, ..stats };
+        prop_assert!(
+            validated_report_score(&fresh, &params, NOW)
+                <= validated_report_score(&settled, &params, NOW)
+        );
+    }
+}
+
+#[test]
+fn probation_ends_after_configured_window() {
+    let params = Params::default();
+    let stats = Stats { base_score: 290, created_at: NOW, ..Stats::default() };
+    let later = NOW + params.probation_seconds as i64;
+
+    assert!(in_probation(&stats, &params, NOW));
+    assert!(!in_probation(&stats, &params, later));
+    assert_eq!(cap_probation_gain(&stats, &params, 500, NOW), 300);
+    assert_eq!(cap_probation_gain(&stats, &params, 500, later), 500);
+    // 观察期内审核通过的举报扣分翻倍
+    assert_eq!(validated_report_score(&stats, &params, NOW), 190);
+}
+
+#[test]
+fn composite_defaults_to_base_score() {
+    let stats = Stats {
+        base_score: 420,
+        sub_scores: SubScores { social: 900, learning: 10, mentoring: 500, safety: 1000 },
+        ..Stats::default()
+    };
+    assert_eq!(composite_score(&stats, &Params::default()), 420);
+}
+
+#[test]
+fn wilson_distinguishes_sample_size() {
+    // 1/1 与 1000/1000 的原始比例相同，但 Wilson 下界差距明显
+    let (one, one_width) = wilson_interval_bps(1, 1, 1_960);
+    let (thousand, thousand_width) = wilson_interval_bps(1_000, 1_000, 1_960);
+    assert!(one < 3_000);
+    assert!(thousand > 9_900);
+    assert!(one_width > thousand_width);
+
+    // 无样本时置信度为 0
+    let estimate = weighted_estimate(&Stats::default(), &Params::default(), NOW);
+    assert_eq!(estimate.confidence_bps, 0);
+}
+
+#[test]
+fn pair_multiplier_halves_after_free_interactions() {
+    let params = Params::default();
+    assert_eq!(pair_multiplier_bps(0, &params), BPS);
+    assert_eq!(pair_multiplier_bps(2, &params), BPS);
+    assert_eq!(pair_multiplier_bps(3, &params), 5_000);
+    assert_eq!(pair_multiplier_bps(4, &params), 2_500);
+    assert_eq!(pair_multiplier_bps(u16::MAX, &params), 0);
+}
+
+#[test]
+fn unrated_interactions_use_the_rater_floor() {
+    let params = Params::default();
+    assert_eq!(unrated_weight_bps(&params), params.rater_floor_bps as u64);
+
+    // 关闭评分人加权时全额计入
+    let disabled = Params { rater_full_weight_score: 0, ..Params::default() };
+    assert_eq!(unrated_weight_bps(&disabled), BPS);
+}
+
+#[test]
+fn pair_multiplier_without_decay_stays_full() {
+    let params = Params { pair_decay_bps: 10_000, ..Params::default() };
+    assert_eq!(pair_multiplier_bps(u16::MAX, &params), BPS);
+}
+
+#[test]
+fn decay_halves_excess_after_one_half_life() {
+    let params = Params::default();
+    let stats = Stats { base_score: 900, last_active: NOW, ..Stats::default() };
+    let half_life = params.decay_half_life_days as i64 * SECONDS_PER_DAY;
+
+    // 超出下限的 800 分在一个半衰期后剩 400
+    assert_eq!(decayed_score(&stats, &params, NOW + half_life), 500);
+    assert_eq!(decayed_score(&stats, &params, NOW + 2 * half_life), 300);
+    // 半个半衰期：100 + 800 / sqrt(2) ≈ 665.7，向上取整
+    assert_eq!(decayed_score(&stats, &params, NOW + half_life / 2), 666);
+}
+
+/// 按程序中的 crank 规则反复调用：只有分数下降时才推进 last_decay_at
+fn crank(mut stats: Stats, params: &Params, start: i64, end: i64, interval: i64) -> Stats {
+    let mut now = start;
+    while now < end {
+        now = (now + interval).min(end);
+        if let Some((score, sub_scores)) = decay_step(&stats, params, now) {
+            stats.base_score = score;
+            stats.sub_scores = sub_scores;
+            stats.last_decay_at = now;
+        }
+    }
+    stats
+}
+
+#[test]
+fn repeated_decay_calls_neither_grief_nor_freeze() {
+    let params = Params { decay_half_life_days: 1, ..Params::default() };
+    let stats = Stats { base_score: 800, last_active: NOW, last_decay_at: NOW, ..Stats::default() };
+    let end = NOW + 2 * SECONDS_PER_DAY;
+    let single = decayed_score(&stats, &params, end);
+
+    // 每 8 秒调用一次，共 21600 次
+    let cranked = crank(stats, &params, NOW, end, 8).base_score;
+    assert!(cranked >= single, "cranked {cranked} < single {single}");
+    // 衰减照常发生：频繁调用只会少扣每次不足 1 分的零头
+    assert!(cranked - single <= (stats.base_score - single) / 10, "cranked {cranked}, single {single}");
+
+    // 每秒调用一次也不会重置计时把衰减冻结住
+    let frozen = crank(stats, &params, NOW, NOW + 600, 1);
+    assert_eq!(frozen.base_score, decayed_score(&stats, &params, NOW + 600));
+}
+
+#[test]
+fn default_params_match_legacy_constants() {
+    let params = Params::default();
+    let stats = Stats {
+        base_score: 100,
+        total_interactions: 1,
+        positive_interactions: 1,
+        ..Stats::default()
+    };
+
+    // (90 - 50) * 5 / 10 = 20，正面率 100% > 80% 再加 20
+    assert_eq!(interaction_score(&stats, &params, InteractionKind::HelpfulResponse, 90, NOW), 140);
+    // 刚活跃：100 * 1.1
+    assert_eq!(weighted_score(&Stats { last_active: NOW, ..stats }, &params, NOW), 110);
+    // 超过 30 天未活跃：100 * 0.8
+    assert_eq!(
+        weighted_score(&Stats { last_active: NOW - 31 * SECONDS_PER_DAY, ..stats }, &params, NOW),
+        80
+    );
+}
@@ -0,0 +1,147 @@
+/*!
+ * Trust Score Merkle
+ * 纪元分数结算的 Merkle 树（no_std + alloc）
+ *
+ * 链下服务为每个纪元构建 (owner, score, epoch) 叶子的 Merkle 树并发布根，
+ * 链上 programs/trust-score 用同一套叶子编码与证明校验结算分数。
+ * 哈希函数通过 `MerkleHasher` 注入：链上使用 sha256 syscall，链下使用 `sha2` feature。
+ */
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+pub type Hash = [u8; 32];
+
+/// 叶子与内部节点的域分隔前缀，防止内部节点被当作叶子伪造证明
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// 证明的最大深度（支持 2^32 个叶子）
+pub const MAX_PROOF_DEPTH: usize = 32;
+
+/// 32 字节哈希函数
+pub trait MerkleHasher {
+    fn hashv(parts: &[&[u8]]) -> Hash;
+}
+
+/// 基于 `sha2` crate 的 sha256，与 Solana `hashv` 结果一致
+#[cfg(feature = "sha2")]
+pub struct Sha256Hasher;
+
+#[cfg(feature = "sha2")]
+impl MerkleHasher for Sha256Hasher {
+    fn hashv(parts: &[&[u8]]) -> Hash {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        for part in parts {
+            hasher.update(part);
+        }
+        hasher.finalize().into()
+    }
+}
+
+/// 一个纪元中某个用户的已提交分数
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScoreLeaf {
+    pub owner: [u8; 32],
+    pub score: u16,
+    pub epoch: u64,
+}
+
+impl ScoreLeaf {
+    /// 叶子哈希：H(0x00 || owner || score_le || epoch_le)
+    pub fn hash<H: MerkleHasher>(&self) -> Hash {
+        H::hashv(&[
+            LEAF_PREFIX,
+            &self.owner,
+            &self.score.to_le_bytes(),
+            &self.epoch.to_le_bytes(),
+        ])
+    }
+}
+
+/// 内部节点哈希：两个子节点按字节序排序后拼接，证明无需携带左右方向
+pub fn hash_pair<H: MerkleHasher>(a: &Hash, b: &Hash) -> Hash {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    H::hashv(&[NODE_PREFIX, left, right])
+}
+
+/// 沿证明路径计算根
+pub fn compute_root<H: MerkleHasher>(leaf: Hash, proof: &[Hash]) -> Hash {
+    proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_pair::<H>(&node, sibling))
+}
+
+/// 校验叶子属于给定根
+pub fn verify<H: MerkleHasher>(root: &Hash, leaf: Hash, proof: &[Hash]) -> bool {
+    proof.len() <= MAX_PROOF_DEPTH && compute_root::<H>(leaf, proof) == *root
+}
+
+/// 完整的 Merkle 树（链下构建、生成证明）
+///
+/// 奇数个节点时，最后一个节点直接提升到上一层，不与自身配对。
+pub struct MerkleTree {
+    /// levels[0] 为叶子哈希，最后一层为根
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    /// 由叶子哈希构建；叶子为空时返回 None
+    pub fn new<H: MerkleHasher>(leaves: Vec<Hash>) -> Option<Self> {
+        if leaves.is_empty() {
+            return None;
+        }
+
+        let mut levels = Vec::new();
+        levels.push(leaves);
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair::<H>(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        Some(Self { levels })
+    }
+
+    /// 由纪元分数构建，叶子顺序与输入一致
+    pub fn from_scores<H: MerkleHasher>(leaves: &[ScoreLeaf]) -> Option<Self> {
+        Self::new::<H>(leaves.iter().map(ScoreLeaf::hash::<H>).collect())
+    }
+
+    pub fn root(&self) -> Hash {
+        self.levels[self.levels.len() - 1][0]
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// 第 index 个叶子的证明（自底向上的兄弟节点）
+    pub fn proof(&self, index: usize) -> Option<Vec<Hash>> {
+        if index >= self.leaf_count() {
+            return None;
+        }
+
+        let mut proof = Vec::new();
+        let mut index = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+
+        Some(proof)
+    }
+}
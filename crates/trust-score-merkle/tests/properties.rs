@@ -0,0 +1,70 @@
+/**
+ * 纪元 Merkle 树属性测试
+ * 每个叶子的证明都能还原根，篡改分数或纪元后校验失败
+ */
+use proptest::prelude::*;
+use trust_score_merkle::*;
+
+fn leaves_strategy() -> impl Strategy<Value = Vec<ScoreLeaf>> {
+    (1u64..1_000, prop::collection::vec((any::<[u8; 32]>(), 0u16..=1000), 1..64)).prop_map(
+        |(epoch, entries)| {
+            entries
+                .into_iter()
+                .map(|(owner, score)| ScoreLeaf { owner, score, epoch })
+                .collect()
+        },
+    )
+}
+
+#[test]
+fn sha256_matches_reference_digest() {
+    let digest = Sha256Hasher::hashv(&[b"ab", b"c"]);
+    assert_eq!(
+        digest[..8],
+        [0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea]
+    );
+}
+
+#[test]
+fn empty_tree_has_no_root() {
+    assert!(MerkleTree::new::<Sha256Hasher>(Vec::new()).is_none());
+}
+
+#[test]
+fn single_leaf_root_is_leaf_hash() {
+    let leaf = ScoreLeaf { owner: [7; 32], score: 420, epoch: 3 };
+    let tree = MerkleTree::from_scores::<Sha256Hasher>(&[leaf]).unwrap();
+
+    assert_eq!(tree.root(), leaf.hash::<Sha256Hasher>());
+    assert!(tree.proof(0).unwrap().is_empty());
+    assert!(tree.proof(1).is_none());
+}
+
+proptest! {
+    #[test]
+    fn every_proof_verifies(leaves in leaves_strategy()) {
+        let tree = MerkleTree::from_scores::<Sha256Hasher>(&leaves).unwrap();
+        let root = tree.root();
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = tree.proof(index).unwrap();
+            prop_assert!(proof.len() <= MAX_PROOF_DEPTH);
+            prop_assert!(verify::<Sha256Hasher>(&root, leaf.hash::<Sha256Hasher>(), &proof));
+        }
+    }
+
+    #[test]
+    fn tampered_leaf_is_rejected(leaves in leaves_strategy(), index in any::<prop::sample::Index>(), delta in 1u16..1000) {
+        let tree = MerkleTree::from_scores::<Sha256Hasher>(&leaves).unwrap();
+        let index = index.index(leaves.len());
+        let proof = tree.proof(index).unwrap();
+        let leaf = leaves[index];
+
+        let tampered = ScoreLeaf { score: (leaf.score + delta) % 1001, ..leaf };
+        prop_assume!(tampered.score != leaf.score);
+        prop_assert!(!verify::<Sha256Hasher>(&tree.root(), tampered.hash::<Sha256Hasher>(), &proof));
+
+        let other_epoch = ScoreLeaf { epoch: leaf.epoch + 1, ..leaf };
+        prop_assert!(!verify::<Sha256Hasher>(&tree.root(), other_epoch.hash::<Sha256Hasher>(), &proof));
+    }
+}
@@ -9,16 +9,20 @@
  */
 
 use anchor_lang::prelude::*;
+use trust_score::{ScoringConfig, TrustScore};
 
 declare_id!("2FBJhP2ecw24W6wpAYF5v42uiPWZB38ubRLyfKyaDVwt");
 
+/// 成为导师所需的最低加权信誉分（0-1000，与默认担保门槛一致；新账户约 110 分）
+pub const MIN_MENTOR_TRUST_SCORE: u16 = 300;
+
 #[program]
 pub mod mentorship {
     use super::*;
 
     /**
      * 初始化导师档案
-     * 要求：等级 >= 20（在链下验证，或通过CPI检查TrustScore）
+     * 要求：加权信誉分 >= MIN_MENTOR_TRUST_SCORE（账户约束中校验 TrustScore）
      */
     pub fn initialize_mentor_profile(
         ctx: Context<InitializeMentorProfile>,
@@ -74,6 +78,13 @@ pub mod mentorship {
 
         require!(mentor_profile.is_active, ErrorCode::MentorInactive);
 
+        // 被暂停或封禁的账户不能建立师徒关系
+        require!(
+            ctx.accounts.mentor_trust_score.is_active(clock.unix_timestamp)
+                && ctx.accounts.mentee_trust_score.is_active(clock.unix_timestamp),
+            ErrorCode::AccountSuspended
+        );
+
         // 初始化师徒关系
         mentorship.mentor = ctx.accounts.mentor.key();
         mentorship.mentee = ctx.accounts.mentee.key();
@@ -378,6 +389,25 @@ pub struct InitializeMentorProfile<'info> {
     
     #[account(mut)]
     pub mentor: Signer<'info>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+        seeds::program = trust_score::ID,
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(
+        seeds = [b"trust_score", mentor.key().as_ref()],
+        bump = mentor_trust_score.bump,
+        seeds::program = trust_score::ID,
+        constraint = trust_score::guard::meets_min_trust(
+            &mentor_trust_score,
+            &scoring_config,
+            MIN_MENTOR_TRUST_SCORE,
+        ) @ ErrorCode::ScoreBelowMinimum,
+    )]
+    pub mentor_trust_score: Account<'info, TrustScore>,
     
     pub system_program: Program<'info, System>,
 }
@@ -409,6 +439,20 @@ pub struct CreateMentorship<'info> {
     
     #[account(mut)]
     pub mentee: Signer<'info>,
+
+    #[account(
+        seeds = [b"trust_score", mentor.key().as_ref()],
+        bump = mentor_trust_score.bump,
+        seeds::program = trust_score::ID,
+    )]
+    pub mentor_trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        seeds = [b"trust_score", mentee.key().as_ref()],
+        bump = mentee_trust_score.bump,
+        seeds::program = trust_score::ID,
+    )]
+    pub mentee_trust_score: Account<'info, TrustScore>,
     
     pub system_program: Program<'info, System>,
 }
@@ -592,5 +636,11 @@ pub enum ErrorCode {
     
     #[msg("Reason too long. Max 200 characters.")]
     ReasonTooLong,
+
+    #[msg("Trust score account is suspended or banned.")]
+    AccountSuspended,
+
+    #[msg("Mentor trust score is below the required minimum or the account is not active.")]
+    ScoreBelowMinimum,
 }
 
@@ -0,0 +1,97 @@
+/**
+ * Mentorship Program Tests
+ * 导师档案的信誉门槛：加权分达标且账户正常才能成为导师
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Mentorship } from "../target/types/mentorship";
+import { TrustScore } from "../target/types/trust_score";
+import { expect } from "chai";
+import {
+  chainTime,
+  createUser,
+  ensureAuthority,
+  ensureScoringConfig,
+  expectError,
+  findPda,
+  overrideScoringParams,
+  registryPda,
+  trustScorePda,
+} from "../../trust-score/tests/helpers";
+
+describe("mentorship", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Mentorship as Program<Mentorship>;
+  const trustScoreProgram = anchor.workspace.TrustScore as Program<TrustScore>;
+
+  const initializeMentorProfile = (mentor: anchor.web3.Keypair) =>
+    program.methods
+      .initializeMentorProfile("Solana", 3)
+      .accountsPartial({
+        mentor: mentor.publicKey,
+        scoringConfig: findPda(trustScoreProgram, [Buffer.from("scoring_config")]),
+        mentorTrustScore: trustScorePda(trustScoreProgram, mentor.publicKey),
+      })
+      .signers([mentor])
+      .rpc();
+
+  before(async () => {
+    await ensureScoringConfig(trustScoreProgram);
+    await ensureAuthority(trustScoreProgram, "moderator");
+  });
+
+  it("信誉未达标的新用户不能成为导师", async () => {
+    const mentor = await createUser(trustScoreProgram);
+    await expectError(initializeMentorProfile(mentor), "ScoreBelowMinimum");
+  });
+
+  it("信誉达标的用户应该可以成为导师", async () => {
+    const mentor = await createUser(trustScoreProgram);
+
+    // 新账户加权分约 110，临时提高活跃度乘数使其超过 MIN_MENTOR_TRUST_SCORE（300）
+    const originalParams = await overrideScoringParams(trustScoreProgram, { activeMultiplierBps: 33_000 });
+    try {
+      await initializeMentorProfile(mentor);
+    } finally {
+      await overrideScoringParams(trustScoreProgram, originalParams);
+    }
+
+    const [mentorProfilePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mentor_profile"), mentor.publicKey.toBuffer()],
+      program.programId
+    );
+    const mentorProfile = await program.account.mentorProfile.fetch(mentorProfilePda);
+    expect(mentorProfile.mentor.equals(mentor.publicKey)).to.be.true;
+    expect(mentorProfile.isActive).to.be.true;
+  });
+
+  it("暂停中的用户不能成为导师", async () => {
+    const mentor = await createUser(trustScoreProgram);
+    const until = (await chainTime(trustScoreProgram)) + 3600;
+
+    await trustScoreProgram.methods
+      .setAccountStatus({ suspended: { until: new anchor.BN(until) } })
+      .accountsPartial({
+        trustScore: trustScorePda(trustScoreProgram, mentor.publicKey),
+        moderatorRegistry: registryPda(trustScoreProgram, "moderator"),
+        moderator: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    await expectError(initializeMentorProfile(mentor), "ScoreBelowMinimum");
+  });
+
+  it("没有 TrustScore 的用户不能成为导师", async () => {
+    const mentor = anchor.web3.Keypair.generate();
+    const signature = await provider.connection.requestAirdrop(
+      mentor.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(signature);
+
+    await expectError(initializeMentorProfile(mentor), "AccountNotInitialized");
+  });
+});
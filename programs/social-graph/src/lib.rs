@@ -9,6 +9,8 @@
  */
 
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use trust_score::TrustScore;
 
 declare_id!("EmKvmnfXrcgmcj4yT2K12rM2dMTZS2MMAfUB8769veHK"); // TODO: 部署后更新
 
@@ -18,7 +20,7 @@ pub mod social_graph {
 
     /**
      * 创建匹配边（Match Edge）
-     * 记录两个用户之间的匹配关系
+     * 记录两个用户之间的匹配关系，需另一方调用 accept_match_edge 确认后才生效
      * 
      * @param user_b: 匹配的另一方
      */
@@ -33,6 +35,13 @@ pub mod social_graph {
         // 确保用户不能和自己匹配
         require!(user_a.key() != user_b, ErrorCode::SelfMatch);
 
+        // 被暂停或封禁的账户不能建立匹配
+        require!(
+            ctx.accounts.user_a_trust_score.is_active(clock.unix_timestamp)
+                && ctx.accounts.user_b_trust_score.is_active(clock.unix_timestamp),
+            ErrorCode::AccountSuspended
+        );
+
         // 按字典序排列，确保匹配边的唯一性
         let (a, b) = if user_a.key() < user_b {
             (user_a.key(), user_b)
@@ -46,6 +55,8 @@ pub mod social_graph {
         match_edge.is_active = true;
         match_edge.interaction_count = 0;
         match_edge.bump = ctx.bumps.match_edge;
+        match_edge.initiator = user_a.key();
+        match_edge.accepted_at = None;
 
         msg!("Match edge created between: {} and {}", a, b);
 
@@ -58,6 +69,41 @@ pub mod social_graph {
         Ok(())
     }
 
+    /**
+     * 确认匹配边
+     * 只能由发起方之外的另一方调用，确认后双方的匹配才被视为有效
+     */
+    pub fn accept_match_edge(ctx: Context<AcceptMatchEdge>) -> Result<()> {
+        let match_edge = &mut ctx.accounts.match_edge;
+        let acceptor = ctx.accounts.acceptor.key();
+        let clock = Clock::get()?;
+
+        require!(
+            (acceptor == match_edge.user_a || acceptor == match_edge.user_b)
+                && acceptor != match_edge.initiator,
+            ErrorCode::Unauthorized
+        );
+        require!(match_edge.is_active, ErrorCode::MatchNotActive);
+        require!(match_edge.accepted_at.is_none(), ErrorCode::MatchAlreadyAccepted);
+        require!(
+            ctx.accounts.acceptor_trust_score.is_active(clock.unix_timestamp),
+            ErrorCode::AccountSuspended
+        );
+
+        match_edge.accepted_at = Some(clock.unix_timestamp);
+
+        msg!("Match edge accepted by: {}", acceptor);
+
+        emit!(MatchEdgeAccepted {
+            user_a: match_edge.user_a,
+            user_b: match_edge.user_b,
+            acceptor,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
     /**
      * 更新匹配边状态
      * 记录互动次数或解除匹配
@@ -98,11 +144,67 @@ pub mod social_graph {
 
     /**
      * 查询匹配边
-     * 验证两个用户是否匹配
+     * 验证两个用户是否匹配（双方均已确认且未停用）
      */
     pub fn verify_match(ctx: Context<VerifyMatch>) -> Result<bool> {
         let match_edge = &ctx.accounts.match_edge;
-        Ok(match_edge.is_active)
+        Ok(match_edge.is_active && match_edge.accepted_at.is_some())
+    }
+
+    /**
+     * 将旧布局的匹配边扩容到当前大小（补齐 initiator、accepted_at）
+     * 旧边无法还原发起方，由调用的一方记为发起方，仍需另一方调用 accept_match_edge 确认
+     */
+    pub fn upgrade_match_edge_layout(ctx: Context<UpgradeMatchEdgeLayout>) -> Result<()> {
+        let account_info = ctx.accounts.match_edge.to_account_info();
+        let participant = ctx.accounts.participant.key();
+        require!(account_info.data_len() < MatchEdge::LEN, ErrorCode::AlreadyUpgraded);
+        require!(
+            account_info.try_borrow_data()?.starts_with(MatchEdge::DISCRIMINATOR),
+            ErrorCode::InvalidMatchEdge
+        );
+
+        // 补足扩容后的租金
+        let rent = Rent::get()?.minimum_balance(MatchEdge::LEN);
+        let top_up = rent.saturating_sub(account_info.lamports());
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.participant.to_account_info(),
+                        to: account_info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        // 新增字段按零扩展：initiator 为空，accepted_at 为 None
+        account_info.resize(MatchEdge::LEN)?;
+
+        let mut match_edge = MatchEdge::try_deserialize(&mut &account_info.try_borrow_data()?[..])?;
+        let expected = Pubkey::create_program_address(
+            &[
+                b"match_edge",
+                match_edge.user_a.as_ref(),
+                match_edge.user_b.as_ref(),
+                &[match_edge.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| ErrorCode::InvalidMatchEdge)?;
+        require_keys_eq!(expected, account_info.key(), ErrorCode::InvalidMatchEdge);
+        require!(
+            participant == match_edge.user_a || participant == match_edge.user_b,
+            ErrorCode::Unauthorized
+        );
+
+        match_edge.initiator = participant;
+        match_edge.try_serialize(&mut &mut account_info.try_borrow_mut_data()?[..])?;
+
+        msg!("Match edge layout upgraded by: {}", participant);
+
+        Ok(())
     }
 }
 
@@ -132,6 +234,12 @@ pub struct MatchEdge {
     
     /// PDA bump
     pub bump: u8,                    // 1 byte
+
+    /// 发起方（create_match_edge 的签名者）
+    pub initiator: Pubkey,           // 32 bytes
+
+    /// 另一方确认时间（未确认为 None）
+    pub accepted_at: Option<i64>,    // 9 bytes (1 + 8)
 }
 
 impl MatchEdge {
@@ -142,7 +250,9 @@ impl MatchEdge {
         1 +  // is_active
         4 +  // interaction_count
         9 +  // deactivated_at (Option)
-        1;   // bump
+        1 +  // bump
+        32 + // initiator
+        9;   // accepted_at (Option)
 }
 
 // ==========================================
@@ -167,10 +277,47 @@ pub struct CreateMatchEdge<'info> {
     
     #[account(mut)]
     pub user_a: Signer<'info>,
+
+    #[account(
+        seeds = [b"trust_score", user_a.key().as_ref()],
+        bump = user_a_trust_score.bump,
+        seeds::program = trust_score::ID,
+    )]
+    pub user_a_trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        seeds = [b"trust_score", user_b.as_ref()],
+        bump = user_b_trust_score.bump,
+        seeds::program = trust_score::ID,
+    )]
+    pub user_b_trust_score: Account<'info, TrustScore>,
     
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct AcceptMatchEdge<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"match_edge",
+            match_edge.user_a.as_ref(),
+            match_edge.user_b.as_ref(),
+        ],
+        bump = match_edge.bump,
+    )]
+    pub match_edge: Account<'info, MatchEdge>,
+
+    pub acceptor: Signer<'info>,
+
+    #[account(
+        seeds = [b"trust_score", acceptor.key().as_ref()],
+        bump = acceptor_trust_score.bump,
+        seeds::program = trust_score::ID,
+    )]
+    pub acceptor_trust_score: Account<'info, TrustScore>,
+}
+
 #[derive(Accounts)]
 pub struct UpdateMatchEdge<'info> {
     #[account(
@@ -188,6 +335,19 @@ pub struct UpdateMatchEdge<'info> {
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct UpgradeMatchEdgeLayout<'info> {
+    /// CHECK: 旧布局无法按当前结构反序列化，指令内校验 discriminator 与 PDA
+    #[account(mut, owner = crate::ID)]
+    pub match_edge: UncheckedAccount<'info>,
+
+    /// 匹配的一方，支付扩容租金并记为发起方
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct VerifyMatch<'info> {
     #[account(
@@ -218,6 +378,18 @@ pub enum ErrorCode {
     
     #[msg("Unauthorized. Only participants can perform this action.")]
     Unauthorized,
+
+    #[msg("Trust score account is suspended or banned.")]
+    AccountSuspended,
+
+    #[msg("Match edge has already been accepted.")]
+    MatchAlreadyAccepted,
+
+    #[msg("Match edge already uses the current layout.")]
+    AlreadyUpgraded,
+
+    #[msg("Invalid match edge account.")]
+    InvalidMatchEdge,
 }
 
 // ==========================================
@@ -231,6 +403,14 @@ pub struct MatchEdgeCreated {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct MatchEdgeAccepted {
+    pub user_a: Pubkey,
+    pub user_b: Pubkey,
+    pub acceptor: Pubkey,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct InteractionRecorded {
     pub user_a: Pubkey,
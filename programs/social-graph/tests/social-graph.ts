@@ -6,6 +6,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { SocialGraph } from "../target/types/social_graph";
+import { TrustScore } from "../target/types/trust_score";
 import { expect } from "chai";
 
 describe("social-graph", () => {
@@ -13,11 +14,18 @@ describe("social-graph", () => {
   anchor.setProvider(provider);
 
   const program = anchor.workspace.SocialGraph as Program<SocialGraph>;
+  const trustScoreProgram = anchor.workspace.TrustScore as Program<TrustScore>;
   const userA = provider.wallet;
   
   // 创建第二个用户
   const userB = anchor.web3.Keypair.generate();
 
+  const trustScorePda = (user: anchor.web3.PublicKey) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("trust_score"), user.toBuffer()],
+      trustScoreProgram.programId
+    )[0];
+
   before(async () => {
     // 给 userB 空投一些 SOL
     const airdropSignature = await provider.connection.requestAirdrop(
@@ -25,6 +33,17 @@ describe("social-graph", () => {
       2 * anchor.web3.LAMPORTS_PER_SOL
     );
     await provider.connection.confirmTransaction(airdropSignature);
+
+    // 建立匹配前双方都需要有效的 TrustScore
+    await trustScoreProgram.methods
+      .initializeTrustScore()
+      .accounts({ owner: userA.publicKey })
+      .rpc();
+    await trustScoreProgram.methods
+      .initializeTrustScore()
+      .accounts({ owner: userB.publicKey })
+      .signers([userB])
+      .rpc();
   });
 
   it("应该成功创建匹配边", async () => {
@@ -42,6 +61,8 @@ describe("social-graph", () => {
       .accounts({
         matchEdge: matchEdgePda,
         userA: userA.publicKey,
+        userATrustScore: trustScorePda(userA.publicKey),
+        userBTrustScore: trustScorePda(userB.publicKey),
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .rpc();
@@ -49,6 +70,60 @@ describe("social-graph", () => {
     const matchEdge = await program.account.matchEdge.fetch(matchEdgePda);
     expect(matchEdge.isActive).to.be.true;
     expect(matchEdge.interactionCount).to.equal(0);
+    expect(matchEdge.initiator.equals(userA.publicKey)).to.be.true;
+    expect(matchEdge.acceptedAt).to.be.null;
+
+    // 另一方确认前不算有效匹配
+    const verified = await program.methods
+      .verifyMatch()
+      .accounts({ matchEdge: matchEdgePda })
+      .view();
+    expect(verified).to.be.false;
+  });
+
+  it("只有另一方可以确认匹配边", async () => {
+    const [minKey, maxKey] = [userA.publicKey, userB.publicKey].sort((a, b) =>
+      a.toBuffer().compare(b.toBuffer())
+    );
+
+    const [matchEdgePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("match_edge"), minKey.toBuffer(), maxKey.toBuffer()],
+      program.programId
+    );
+
+    try {
+      await program.methods
+        .acceptMatchEdge()
+        .accountsPartial({
+          matchEdge: matchEdgePda,
+          acceptor: userA.publicKey,
+          acceptorTrustScore: trustScorePda(userA.publicKey),
+        })
+        .rpc();
+
+      expect.fail("应该抛出错误");
+    } catch (err) {
+      expect((err as anchor.AnchorError).error.errorCode.code).to.equal("Unauthorized");
+    }
+
+    await program.methods
+      .acceptMatchEdge()
+      .accountsPartial({
+        matchEdge: matchEdgePda,
+        acceptor: userB.publicKey,
+        acceptorTrustScore: trustScorePda(userB.publicKey),
+      })
+      .signers([userB])
+      .rpc();
+
+    const matchEdge = await program.account.matchEdge.fetch(matchEdgePda);
+    expect(matchEdge.acceptedAt).to.exist;
+
+    const verified = await program.methods
+      .verifyMatch()
+      .accounts({ matchEdge: matchEdgePda })
+      .view();
+    expect(verified).to.be.true;
   });
 
   it("应该正确记录互动次数", async () => {
@@ -119,6 +194,78 @@ describe("social-graph", () => {
     expect(result).to.be.false; // 因为上面已经停用了
   });
 
+  describe("旧布局匹配边", () => {
+    // 与 tests/fixtures/legacy-match-edge.json 对应，由 Anchor.toml 预先加载到测试验证器
+    const legacyEdge = new anchor.web3.PublicKey("BguiTWmM3THvEnRgW77aDw9mxrRmwAXg1e3DfXJiUUZQ");
+    const participantA = anchor.web3.Keypair.fromSeed(new Uint8Array(32).fill(1));
+    const participantB = anchor.web3.Keypair.fromSeed(new Uint8Array(32).fill(2));
+
+    const upgrade = (participant: anchor.web3.Keypair) =>
+      program.methods
+        .upgradeMatchEdgeLayout()
+        .accountsPartial({ matchEdge: legacyEdge, participant: participant.publicKey })
+        .signers([participant])
+        .rpc();
+
+    const expectErrorCode = async (promise: Promise<unknown>, code: string) => {
+      try {
+        await promise;
+        expect.fail("应该抛出错误");
+      } catch (err) {
+        expect((err as anchor.AnchorError).error.errorCode.code).to.equal(code);
+      }
+    };
+
+    before(async () => {
+      for (const participant of [participantA, participantB]) {
+        const signature = await provider.connection.requestAirdrop(
+          participant.publicKey,
+          anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(signature);
+      }
+
+      await trustScoreProgram.methods
+        .initializeTrustScore()
+        .accounts({ owner: participantB.publicKey })
+        .signers([participantB])
+        .rpc();
+    });
+
+    it("匹配双方之外的用户不能升级", async () => {
+      await expectErrorCode(upgrade(userB), "Unauthorized");
+    });
+
+    it("应该把旧匹配边扩容为当前布局，并由调用方记为发起方", async () => {
+      await upgrade(participantA);
+
+      const matchEdge = await program.account.matchEdge.fetch(legacyEdge);
+      expect(matchEdge.interactionCount).to.equal(5);
+      expect(matchEdge.isActive).to.be.true;
+      expect(matchEdge.initiator.equals(participantA.publicKey)).to.be.true;
+      expect(matchEdge.acceptedAt).to.be.null;
+
+      await expectErrorCode(upgrade(participantB), "AlreadyUpgraded");
+    });
+
+    it("升级后仍需另一方确认", async () => {
+      const verifyLegacy = () => program.methods.verifyMatch().accounts({ matchEdge: legacyEdge }).view();
+      expect(await verifyLegacy()).to.be.false;
+
+      await program.methods
+        .acceptMatchEdge()
+        .accountsPartial({
+          matchEdge: legacyEdge,
+          acceptor: participantB.publicKey,
+          acceptorTrustScore: trustScorePda(participantB.publicKey),
+        })
+        .signers([participantB])
+        .rpc();
+
+      expect(await verifyLegacy()).to.be.true;
+    });
+  });
+
   it("不应该允许用户与自己匹配", async () => {
     const [matchEdgePda] = anchor.web3.PublicKey.findProgramAddressSync(
       [Buffer.from("match_edge"), userA.publicKey.toBuffer(), userA.publicKey.toBuffer()],
@@ -131,6 +278,8 @@ describe("social-graph", () => {
         .accounts({
           matchEdge: matchEdgePda,
           userA: userA.publicKey,
+          userATrustScore: trustScorePda(userA.publicKey),
+          userBTrustScore: trustScorePda(userA.publicKey),
           systemProgram: anchor.web3.SystemProgram.programId,
         })
         .rpc();
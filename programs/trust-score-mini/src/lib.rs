@@ -2,24 +2,106 @@ use anchor_lang::prelude::*;
 
 declare_id!("3FWDkwEPfVVZmxXS4f3pDaJpg4qf7GL5ir89DtXSwAjR");
 
+// 完整版 trust-score 程序，迁移时由其 mini_migrator PDA 签名关闭账户
+pub const TRUST_SCORE_PROGRAM_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR2");
+
 #[program]
 pub mod trust_score_mini {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, score: u64) -> Result<()> {
+    // 仅程序升级权限可初始化并成为 admin；attester 负责记录分数变化
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        attester: Pubkey,
+        min_score: u64,
+        max_score: u64,
+        initial_score: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.bump = *ctx.bumps.get("config").unwrap();
+        config.set(attester, min_score, max_score, initial_score)
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        attester: Pubkey,
+        min_score: u64,
+        max_score: u64,
+        initial_score: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .config
+            .set(attester, min_score, max_score, initial_score)
+    }
+
+    // 新账户统一从 config.initial_score 开始
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         let trust_account = &mut ctx.accounts.trust_account;
         trust_account.owner = ctx.accounts.user.key();
-        trust_account.score = score;
+        trust_account.score = ctx.accounts.config.initial_score;
         trust_account.total_interactions = 0;
         Ok(())
     }
 
+    // 仅 attester 或 admin 可调用，结果饱和截断到 [min_score, max_score]
     pub fn update_score(ctx: Context<UpdateScore>, delta: i64) -> Result<()> {
+        let config = &ctx.accounts.config;
         let trust_account = &mut ctx.accounts.trust_account;
-        trust_account.score = (trust_account.score as i64 + delta) as u64;
-        trust_account.total_interactions += 1;
+
+        let old_score = trust_account.score;
+        trust_account.score = config.clamp(old_score.saturating_add_signed(delta));
+        trust_account.total_interactions = trust_account.total_interactions.saturating_add(1);
+
+        emit!(ScoreUpdated {
+            owner: trust_account.owner,
+            old_score,
+            new_score: trust_account.score,
+            delta,
+            authority: ctx.accounts.authority.key(),
+        });
         Ok(())
     }
+
+    // 仅供 trust-score 的 migrate_from_mini 通过 CPI 调用，租金退还给 owner
+    pub fn close(_ctx: Context<Close>) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = Config::LEN,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::TrustScoreMini>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
@@ -32,6 +114,8 @@ pub struct Initialize<'info> {
         bump
     )]
     pub trust_account: Account<'info, TrustAccount>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
     #[account(mut)]
     pub user: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -41,11 +125,39 @@ pub struct Initialize<'info> {
 pub struct UpdateScore<'info> {
     #[account(
         mut,
-        seeds = [b"trust_score", user.key().as_ref()],
+        seeds = [b"trust_score", trust_account.owner.as_ref()],
         bump
     )]
     pub trust_account: Account<'info, TrustAccount>,
-    pub user: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = authority.key() == config.attester
+            || authority.key() == config.admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Close<'info> {
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        seeds = [b"trust_score", owner.key().as_ref()],
+        bump
+    )]
+    pub trust_account: Account<'info, TrustAccount>,
+    /// CHECK: 账户 owner，只接收退还的租金
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+    #[account(
+        seeds = [b"mini_migrator"],
+        bump,
+        seeds::program = TRUST_SCORE_PROGRAM_ID
+    )]
+    pub migrator: Signer<'info>,
 }
 
 #[account]
@@ -55,6 +167,51 @@ pub struct TrustAccount {
     pub total_interactions: u64,
 }
 
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub attester: Pubkey,
+    pub min_score: u64,
+    pub max_score: u64,
+    pub initial_score: u64,
+    pub bump: u8,
+}
 
+impl Config {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1;
 
+    fn set(&mut self, attester: Pubkey, min_score: u64, max_score: u64, initial_score: u64) -> Result<()> {
+        require!(
+            min_score <= initial_score && initial_score <= max_score,
+            ErrorCode::InvalidScoreRange
+        );
+        self.attester = attester;
+        self.min_score = min_score;
+        self.max_score = max_score;
+        self.initial_score = initial_score;
+        Ok(())
+    }
 
+    fn clamp(&self, score: u64) -> u64 {
+        score.clamp(self.min_score, self.max_score)
+    }
+}
+
+#[event]
+pub struct ScoreUpdated {
+    pub owner: Pubkey,
+    pub old_score: u64,
+    pub new_score: u64,
+    pub delta: i64,
+    pub authority: Pubkey,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Signer is not the configured attester or admin.")]
+    Unauthorized,
+    #[msg("Scores must satisfy min_score <= initial_score <= max_score.")]
+    InvalidScoreRange,
+    #[msg("Signer is not the program upgrade authority.")]
+    NotUpgradeAuthority,
+}
@@ -0,0 +1,197 @@
+//! trust-score-mini 测试
+//! update_score 的授权与截断、close 只接受 trust-score 的 migrator PDA 签名
+//! 通过程序入口调用，账户约束与处理函数一并执行
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::InstructionData;
+use trust_score_mini::{instruction, Config, ErrorCode, TrustAccount, TRUST_SCORE_PROGRAM_ID};
+
+const MIN_SCORE: u64 = 10;
+const MAX_SCORE: u64 = 200;
+const INITIAL_SCORE: u64 = 100;
+const ACCOUNT_RENT: u64 = 1_000_000;
+
+/// 按运行时的序列化布局保存的账户：key 之前 4 字节为原始数据长度，data 之前 8 字节为当前长度
+/// （close 时 resize 会读写这两处）
+#[repr(C)]
+struct Fixture {
+    original_data_len: u32,
+    key: Pubkey,
+    owner: Pubkey,
+    lamports: u64,
+    /// 前 8 字节为长度前缀
+    data: Vec<u8>,
+    is_signer: bool,
+}
+
+impl Fixture {
+    fn new<T: AccountSerialize>(key: Pubkey, account: &T) -> Self {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        Self::raw(key, trust_score_mini::ID, data, false)
+    }
+
+    fn wallet(key: Pubkey, is_signer: bool) -> Self {
+        Self::raw(key, anchor_lang::system_program::ID, Vec::new(), is_signer)
+    }
+
+    fn raw(key: Pubkey, owner: Pubkey, data: Vec<u8>, is_signer: bool) -> Self {
+        let mut prefixed = (data.len() as u64).to_le_bytes().to_vec();
+        prefixed.extend_from_slice(&data);
+        Self {
+            original_data_len: data.len() as u32,
+            key,
+            owner,
+            lamports: ACCOUNT_RENT,
+            data: prefixed,
+            is_signer,
+        }
+    }
+
+    fn info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.key,
+            self.is_signer,
+            true,
+            &mut self.lamports,
+            &mut self.data[8..],
+            &self.owner,
+            false,
+            0,
+        )
+    }
+}
+
+fn trust_account_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"trust_score", owner.as_ref()], &trust_score_mini::ID).0
+}
+
+fn migrator_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"mini_migrator"], &TRUST_SCORE_PROGRAM_ID).0
+}
+
+/// 配置与一名用户的 TrustAccount
+struct Mini {
+    admin: Pubkey,
+    attester: Pubkey,
+    user: Pubkey,
+    config: Fixture,
+    trust_account: Fixture,
+}
+
+impl Mini {
+    fn new(score: u64) -> Self {
+        let (admin, attester, user) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (config_key, bump) = Pubkey::find_program_address(&[b"config"], &trust_score_mini::ID);
+        let config = Config {
+            admin,
+            attester,
+            min_score: MIN_SCORE,
+            max_score: MAX_SCORE,
+            initial_score: INITIAL_SCORE,
+            bump,
+        };
+        let trust_account = TrustAccount {
+            owner: user,
+            score,
+            total_interactions: 0,
+        };
+
+        Self {
+            admin,
+            attester,
+            user,
+            config: Fixture::new(config_key, &config),
+            trust_account: Fixture::new(trust_account_pda(&user), &trust_account),
+        }
+    }
+
+    fn update_score(&mut self, authority: Pubkey, delta: i64) -> std::result::Result<(), ProgramError> {
+        let mut authority = Fixture::wallet(authority, true);
+        let accounts = [self.trust_account.info(), self.config.info(), authority.info()];
+        trust_score_mini::entry(
+            &trust_score_mini::ID,
+            &accounts,
+            &instruction::UpdateScore { delta }.data(),
+        )
+    }
+
+    fn close(&mut self, owner: &mut Fixture, migrator: Pubkey) -> std::result::Result<(), ProgramError> {
+        let mut migrator = Fixture::wallet(migrator, true);
+        let accounts = [self.trust_account.info(), owner.info(), migrator.info()];
+        trust_score_mini::entry(&trust_score_mini::ID, &accounts, &instruction::Close {}.data())
+    }
+
+    fn trust_account(&self) -> TrustAccount {
+        TrustAccount::try_deserialize(&mut &self.trust_account.data[8..]).unwrap()
+    }
+}
+
+fn program_error(code: impl Into<u32>) -> ProgramError {
+    ProgramError::Custom(code.into())
+}
+
+#[test]
+fn update_score_applies_delta_within_bounds() {
+    let mut mini = Mini::new(INITIAL_SCORE);
+
+    mini.update_score(mini.attester, 25).unwrap();
+    let account = mini.trust_account();
+    assert_eq!(account.score, 125);
+    assert_eq!(account.total_interactions, 1);
+
+    // 超出范围时饱和截断到 [min_score, max_score]
+    mini.update_score(mini.attester, 1_000).unwrap();
+    assert_eq!(mini.trust_account().score, MAX_SCORE);
+    mini.update_score(mini.admin, i64::MIN).unwrap();
+    assert_eq!(mini.trust_account().score, MIN_SCORE);
+    assert_eq!(mini.trust_account().total_interactions, 3);
+}
+
+#[test]
+fn update_score_rejects_other_signers() {
+    let mut mini = Mini::new(INITIAL_SCORE);
+
+    assert_eq!(
+        mini.update_score(Pubkey::new_unique(), 25).unwrap_err(),
+        program_error(ErrorCode::Unauthorized)
+    );
+    assert_eq!(mini.trust_account().score, INITIAL_SCORE);
+    assert_eq!(mini.trust_account().total_interactions, 0);
+}
+
+#[test]
+fn close_refunds_owner_when_signed_by_migrator() {
+    let mut mini = Mini::new(INITIAL_SCORE);
+    let mut owner = Fixture::wallet(mini.user, false);
+
+    mini.close(&mut owner, migrator_pda()).unwrap();
+
+    assert_eq!(owner.lamports, 2 * ACCOUNT_RENT);
+    assert_eq!(mini.trust_account.lamports, 0);
+    assert_eq!(mini.trust_account.owner, anchor_lang::system_program::ID);
+    assert_eq!(mini.trust_account.data[..8], 0u64.to_le_bytes());
+}
+
+#[test]
+fn close_rejects_other_signers_and_owners() {
+    let mut mini = Mini::new(INITIAL_SCORE);
+    let mut owner = Fixture::wallet(mini.user, false);
+
+    // 只有 trust-score 的 migrator PDA 可以关闭
+    assert_eq!(
+        mini.close(&mut owner, Pubkey::new_unique()).unwrap_err(),
+        program_error(anchor_lang::error::ErrorCode::ConstraintSeeds)
+    );
+
+    // 租金只能退还给账户 owner：换成他人时按其派生的 PDA 与账户不符
+    let mut other = Fixture::wallet(Pubkey::new_unique(), false);
+    assert_eq!(
+        mini.close(&mut other, migrator_pda()).unwrap_err(),
+        program_error(anchor_lang::error::ErrorCode::ConstraintSeeds)
+    );
+
+    assert_eq!(mini.trust_account.lamports, ACCOUNT_RENT);
+    assert_eq!(mini.trust_account().owner, mini.user);
+}
@@ -4,6 +4,12 @@
  */
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+use trust_score_engine as engine;
+use trust_score_merkle as merkle;
 
 declare_id!("8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR2"); // TODO: 部署后更新
 
@@ -19,7 +25,7 @@ pub mod trust_score {
         let clock = Clock::get()?;
 
         trust_score.owner = ctx.accounts.owner.key();
-        trust_score.base_score = 100; // 初始分数
+        trust_score.base_score = 0;
         trust_score.total_interactions = 0;
         trust_score.positive_interactions = 0;
         trust_score.reports_received = 0;
@@ -27,12 +33,34 @@ pub mod trust_score {
         trust_score.learning_streak = 0;
         trust_score.last_active = clock.unix_timestamp;
         trust_score.bump = ctx.bumps.trust_score;
+        trust_score.attestation_nonce = 0;
+        trust_score.last_decay_at = clock.unix_timestamp;
+        trust_score.outstanding_vouches = 0;
+        trust_score.vouches_received = 0;
+        trust_score.sub_scores = engine::SubScores::uniform(TrustScore::INITIAL_SCORE).into();
+        trust_score.created_at = clock.unix_timestamp;
+
+        let score_history = &mut ctx.accounts.score_history;
+        score_history.owner = ctx.accounts.owner.key();
+        score_history.bump = ctx.bumps.score_history;
+
+        trust_score.apply_score_change(
+            score_history,
+            ScoreChange {
+                new_score: TrustScore::INITIAL_SCORE,
+                cause: ScoreCause::Initialized,
+                category: None,
+                actor: ctx.accounts.owner.key(),
+                config_version: ctx.accounts.scoring_config.version,
+                timestamp: clock.unix_timestamp,
+            },
+        );
 
         msg!("Trust Score initialized for: {}", ctx.accounts.owner.key());
         
         emit!(TrustScoreInitialized {
             owner: ctx.accounts.owner.key(),
-            initial_score: 100,
+            initial_score: TrustScore::INITIAL_SCORE,
             timestamp: clock.unix_timestamp,
         });
         
@@ -41,6 +69,10 @@ pub mod trust_score {
 
     /**
      * 记录互动行为（匹配、聊天、学习）
+     * 所有类型都必须传入互动对方及其计数账户，同一对方的重复互动加分递减
+     * Match / Chat 还必须传入 social-graph 中有效的 MatchEdge
+     * 对方无需签名，只有证明方证明时才以对方的 TrustScore 作为评分人，按其加权分缩放分数变化；
+     * 无证明或对方尚未创建时按评分人权重下限计入
      */
     pub fn record_interaction(
         ctx: Context<RecordInteraction>,
@@ -48,36 +80,194 @@ pub mod trust_score {
         quality_score: u8,
     ) -> Result<()> {
         require!(quality_score <= 100, ErrorCode::InvalidQualityScore);
+        require!(
+            !interaction_type.is_negative(),
+            ErrorCode::NegativeInteractionRequiresAttester
+        );
+
+        let counterparty = ctx.accounts.counterparty.key();
+        require!(
+            counterparty != ctx.accounts.owner.key(),
+            ErrorCode::SelfInteraction
+        );
+        if interaction_type.requires_match() {
+            let match_edge = ctx
+                .accounts
+                .match_edge
+                .as_ref()
+                .ok_or(ErrorCode::MatchEdgeRequired)?;
+            verify_match_edge(match_edge, &ctx.accounts.owner.key(), &counterparty)?;
+        }
 
         let trust_score = &mut ctx.accounts.trust_score;
         let clock = Clock::get()?;
+        require!(trust_score.is_active(clock.unix_timestamp), ErrorCode::AccountSuspended);
+
+        // 证明方签名校验（开启强制模式时）
+        let message = interaction_attestation_message(
+            &trust_score.owner,
+            Some(&counterparty),
+            interaction_type,
+            quality_score,
+            trust_score.attestation_nonce,
+        );
+        let attester = verify_attestation(
+            &ctx.accounts.attester_registry,
+            ctx.accounts.attester.as_ref(),
+            ctx.accounts.instructions_sysvar.as_ref(),
+            &message,
+            false,
+        )?;
+        if attester.is_some() {
+            trust_score.attestation_nonce += 1;
+        }
+
+        let scoring_config = &ctx.accounts.scoring_config;
+        let params: engine::Params = scoring_config.params.into();
 
         // 更新互动次数
         trust_score.total_interactions += 1;
 
         // 根据质量评分更新正面互动
-        if quality_score >= 60 {
+        if engine::is_positive(&params, interaction_type.into(), quality_score) {
             trust_score.positive_interactions += 1;
         }
 
         // 实时计算信誉分
-        let new_score = calculate_trust_score(trust_score, interaction_type, quality_score)?;
-        trust_score.base_score = new_score;
+        let mut new_score = engine::interaction_score(
+            &trust_score.stats(),
+            &params,
+            interaction_type.into(),
+            quality_score,
+            clock.unix_timestamp,
+        );
+
+        // 按评分人（对方）的加权分缩放本次分数变化
+        // 对方由 owner 任意指定且无需签名，只有证明方背书时才采信对方的分数
+        let multiplier = if attester.is_some() {
+            rater_weight_bps(&ctx.accounts.rater_trust_score, &params, clock.unix_timestamp)?
+        } else {
+            engine::unrated_weight_bps(&params)
+        };
+        new_score = engine::scale_adjustment(trust_score.base_score, new_score, multiplier);
+
+        // 同一对方的重复互动加分递减
+        let interaction_pair = &mut ctx.accounts.interaction_pair;
+        if interaction_pair.owner == Pubkey::default() {
+            interaction_pair.owner = trust_score.owner;
+            interaction_pair.counterparty = counterparty;
+            interaction_pair.window_start = clock.unix_timestamp;
+            interaction_pair.bump = ctx.bumps.interaction_pair;
+        }
+        new_score = apply_pair_window(
+            interaction_pair,
+            trust_score.base_score,
+            new_score,
+            &params,
+            clock.unix_timestamp,
+        );
+
         trust_score.last_active = clock.unix_timestamp;
+        let old_score = trust_score.apply_score_change(
+            &mut ctx.accounts.score_history,
+            ScoreChange {
+                new_score,
+                cause: ScoreCause::Interaction,
+                category: Some(engine::InteractionKind::from(interaction_type).category()),
+                actor: attester.unwrap_or(ctx.accounts.owner.key()),
+                config_version: scoring_config.version,
+                timestamp: clock.unix_timestamp,
+            },
+        );
 
         msg!("Interaction recorded. New score: {}", new_score);
         
         emit!(TrustScoreUpdated {
             owner: ctx.accounts.owner.key(),
-            old_score: trust_score.base_score,
+            old_score,
             new_score,
             interaction_type,
+            counterparty: Some(counterparty),
             timestamp: clock.unix_timestamp,
+            config_version: scoring_config.version,
         });
         
         Ok(())
     }
 
+    /**
+     * 记录负面行为（爽约、垃圾信息、骚扰、诈骗）
+     * 必须由已注册的证明方签名或提供 ed25519 签名载荷，被记录用户无需签名
+     */
+    pub fn record_negative_interaction(
+        ctx: Context<RecordNegativeInteraction>,
+        interaction_type: InteractionType,
+    ) -> Result<()> {
+        require!(interaction_type.is_negative(), ErrorCode::InvalidInteractionType);
+
+        let counterparty = ctx.accounts.counterparty.as_ref().map(|account| account.key());
+        let trust_score = &mut ctx.accounts.trust_score;
+        let clock = Clock::get()?;
+
+        // 负面行为总是要求证明（质量分字段固定为 0）
+        let message = interaction_attestation_message(
+            &trust_score.owner,
+            counterparty.as_ref(),
+            interaction_type,
+            0,
+            trust_score.attestation_nonce,
+        );
+        let attester = verify_attestation(
+            &ctx.accounts.attester_registry,
+            ctx.accounts.attester.as_ref(),
+            ctx.accounts.instructions_sysvar.as_ref(),
+            &message,
+            true,
+        )?;
+        trust_score.attestation_nonce += 1;
+
+        let scoring_config = &ctx.accounts.scoring_config;
+
+        trust_score.total_interactions += 1;
+        let new_score = engine::interaction_score(
+            &trust_score.stats(),
+            &scoring_config.params.into(),
+            interaction_type.into(),
+            0,
+            clock.unix_timestamp,
+        );
+        let actor = attester.unwrap_or(trust_score.owner);
+        let old_score = trust_score.apply_score_change(
+            &mut ctx.accounts.score_history,
+            ScoreChange {
+                new_score,
+                cause: ScoreCause::NegativeInteraction,
+                category: Some(engine::Category::Safety),
+                actor,
+                config_version: scoring_config.version,
+                timestamp: clock.unix_timestamp,
+            },
+        );
+
+        msg!(
+            "Negative interaction recorded. Score: {} -> {}",
+            old_score,
+            trust_score.base_score
+        );
+
+        emit!(TrustScoreUpdated {
+            owner: trust_score.owner,
+            old_score,
+            new_score: trust_score.base_score,
+            interaction_type,
+            counterparty,
+            timestamp: clock.unix_timestamp,
+            config_version: scoring_config.version,
+        });
+
+        Ok(())
+    }
+
     /**
      * 记录学习进度
      */
@@ -88,21 +278,50 @@ pub mod trust_score {
     ) -> Result<()> {
         let trust_score = &mut ctx.accounts.trust_score;
         let clock = Clock::get()?;
+        require!(trust_score.is_active(clock.unix_timestamp), ErrorCode::AccountSuspended);
 
-        // 更新学习连胜
-        let time_since_last = clock.unix_timestamp - trust_score.last_active;
-        if time_since_last < 86400 {
-            trust_score.learning_streak += 1;
-        } else {
-            trust_score.learning_streak = 1;
+        // 证明方签名校验（开启强制模式时）
+        let message = learning_attestation_message(
+            &trust_score.owner,
+            cards_viewed,
+            engagement_score,
+            trust_score.attestation_nonce,
+        );
+        let attester = verify_attestation(
+            &ctx.accounts.attester_registry,
+            ctx.accounts.attester.as_ref(),
+            ctx.accounts.instructions_sysvar.as_ref(),
+            &message,
+            false,
+        )?;
+        if attester.is_some() {
+            trust_score.attestation_nonce += 1;
         }
 
-        // 学习活跃度提升信誉分
-        if engagement_score > 70 && trust_score.learning_streak > 7 {
-            trust_score.base_score = (trust_score.base_score + 5).min(1000);
-        }
+        // 更新学习连胜
+        trust_score.learning_streak =
+            engine::next_learning_streak(&trust_score.stats(), clock.unix_timestamp);
 
+        // 学习活跃度提升信誉分
+        let scoring_config = &ctx.accounts.scoring_config;
+        let new_score = engine::learning_score(
+            &trust_score.stats(),
+            &scoring_config.params.into(),
+            engagement_score,
+            clock.unix_timestamp,
+        );
         trust_score.last_active = clock.unix_timestamp;
+        trust_score.apply_score_change(
+            &mut ctx.accounts.score_history,
+            ScoreChange {
+                new_score,
+                cause: ScoreCause::Learning,
+                category: Some(engine::Category::Learning),
+                actor: attester.unwrap_or(ctx.accounts.owner.key()),
+                config_version: scoring_config.version,
+                timestamp: clock.unix_timestamp,
+            },
+        );
 
         msg!("Learning activity recorded. Streak: {}", trust_score.learning_streak);
         
@@ -112,6 +331,8 @@ pub mod trust_score {
             engagement_score,
             current_streak: trust_score.learning_streak,
             timestamp: clock.unix_timestamp,
+            new_score: trust_score.base_score,
+            config_version: scoring_config.version,
         });
         
         Ok(())
@@ -119,6 +340,8 @@ pub mod trust_score {
 
     /**
      * 举报用户
+     * 每个 (举报人, 被举报人) 只能举报一次，举报在审核前不影响信誉分
+     * 暂停或封禁中的账户不能举报他人
      */
     pub fn report_user(
         ctx: Context<ReportUser>,
@@ -126,14 +349,63 @@ pub mod trust_score {
         evidence_hash: [u8; 32],
     ) -> Result<()> {
         require!(reason.len() <= 200, ErrorCode::ReasonTooLong);
+        require!(
+            ctx.accounts.reporter.key() != ctx.accounts.reported_user.key(),
+            ErrorCode::SelfReport
+        );
+
+        let report = &mut ctx.accounts.report;
+        let clock = Clock::get()?;
+        require!(
+            ctx.accounts.reporter_trust_score.is_active(clock.unix_timestamp),
+            ErrorCode::AccountSuspended
+        );
+
+        report.reporter = ctx.accounts.reporter.key();
+        report.reported_user = ctx.accounts.reported_user.key();
+        report.reason = reason;
+        report.evidence_hash = evidence_hash;
+        report.status = ReportStatus::Pending;
+        report.created_at = clock.unix_timestamp;
+        report.resolved_at = None;
+        report.resolved_by = None;
+        report.bump = ctx.bumps.report;
+        report.voucher_penalties = Vec::new();
+        report.bond_settled = false;
+
+        // 托管举报保证金
+        let bond_lamports = ctx.accounts.report_config.bond_lamports;
+        if bond_lamports > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.reporter.to_account_info(),
+                        to: ctx.accounts.bond_vault.to_account_info(),
+                    },
+                ),
+                bond_lamports,
+            )?;
+        }
+        report.bond_lamports = bond_lamports;
 
         let reported_score = &mut ctx.accounts.reported_trust_score;
         reported_score.reports_received += 1;
+        reported_score.pending_reports = reported_score.pending_reports.saturating_add(1);
 
-        // 如果举报次数过多，降低信誉分
-        if reported_score.reports_received > 3 {
-            reported_score.base_score = reported_score.base_score.saturating_sub(50);
-        }
+        // 提交举报不扣分，仍记录一条审计事件
+        let current_score = reported_score.base_score;
+        reported_score.apply_score_change(
+            &mut ctx.accounts.reported_score_history,
+            ScoreChange {
+                new_score: current_score,
+                cause: ScoreCause::ReportFiled,
+                category: None,
+                actor: ctx.accounts.reporter.key(),
+                config_version: ctx.accounts.scoring_config.version,
+                timestamp: report.created_at,
+            },
+        );
 
         msg!("User reported. Total reports: {}", reported_score.reports_received);
         
@@ -142,231 +414,4630 @@ pub mod trust_score {
             reported_user: ctx.accounts.reported_user.key(),
             total_reports: reported_score.reports_received,
             new_score: reported_score.base_score,
+            bond_lamports,
         });
         
         Ok(())
     }
 
     /**
-     * 获取加权信誉分
+     * 审核通过举报（仅版主）
+     * 只有通过审核的举报才会计入 reports_validated 并扣减信誉分
+     * 保证金继续托管，申诉期结束后由举报人通过 withdraw_report_bond 取回
+     * remaining_accounts: 被举报人收到的全部担保，按 [Vouch, 担保人 TrustScore, 担保人 ScoreHistory] 三个一组传入，
+     * 组数必须等于被举报人的 vouches_received，每个担保人都会受到连带扣分（TrustScore 已关闭的担保人除外）
      */
-    pub fn get_weighted_score(ctx: Context<GetScore>) -> Result<u16> {
-        let trust_score = &ctx.accounts.trust_score;
-        let weighted_score = calculate_weighted_score(trust_score)?;
-        
-        msg!("Weighted score: {}", weighted_score);
-        Ok(weighted_score)
-    }
-}
+    pub fn validate_report<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveReport<'info>>,
+    ) -> Result<()> {
+        let report = &mut ctx.accounts.report;
+        let clock = Clock::get()?;
 
-// ==========================================
-// 辅助函数
-// ==========================================
+        require!(report.status == ReportStatus::Pending, ErrorCode::ReportAlreadyResolved);
 
-fn calculate_trust_score(
-    trust_score: &TrustScore,
-    interaction_type: InteractionType,
-    quality_score: u8,
-) -> Result<u16> {
-    let mut score = trust_score.base_score as i32;
-
-    // 根据互动类型调整权重
-    let weight = match interaction_type {
-        InteractionType::Match => 2,
-        InteractionType::Chat => 3,
-        InteractionType::HelpfulResponse => 5,
-        InteractionType::SharedContent => 4,
-    };
+        report.status = ReportStatus::Validated;
+        report.resolved_at = Some(clock.unix_timestamp);
+        report.resolved_by = Some(ctx.accounts.moderator.key());
+
+        let reported_score = &mut ctx.accounts.reported_trust_score;
+        reported_score.reports_validated += 1;
+        reported_score.pending_reports = reported_score.pending_reports.saturating_sub(1);
+        let new_score = engine::validated_report_score(
+            &reported_score.stats(),
+            &ctx.accounts.scoring_config.params.into(),
+            clock.unix_timestamp,
+        );
+        report.penalty_applied = reported_score.base_score - new_score;
+        reported_score.apply_score_change(
+            &mut ctx.accounts.reported_score_history,
+            ScoreChange {
+                new_score,
+                cause: ScoreCause::ReportValidated,
+                category: Some(engine::Category::Safety),
+                actor: ctx.accounts.moderator.key(),
+                config_version: ctx.accounts.scoring_config.version,
+                timestamp: clock.unix_timestamp,
+            },
+        );
+
+        msg!("Report validated. Validated reports: {}", reported_score.reports_validated);
+
+        emit!(ReportResolved {
+            report: report.key(),
+            reporter: report.reporter,
+            reported_user: report.reported_user,
+            moderator: ctx.accounts.moderator.key(),
+            status: report.status,
+            new_score: reported_score.base_score,
+            bond_lamports: report.bond_lamports,
+            config_version: ctx.accounts.scoring_config.version,
+        });
 
-    // 根据质量评分调整
-    let adjustment = ((quality_score as i32 - 50) * weight) / 10;
-    score += adjustment;
+        // 担保人连带扣分
+        let scoring_config = &ctx.accounts.scoring_config;
+        let vouches = ctx.remaining_accounts.chunks_exact(3);
+        require!(vouches.remainder().is_empty(), ErrorCode::InvalidRemainingAccounts);
+        // 必须传入全部担保，不能通过省略担保人规避连带扣分
+        require!(
+            vouches.len() == reported_score.vouches_received as usize,
+            ErrorCode::IncompleteVouchAccounts
+        );
+        require!(
+            vouches.len() <= TrustScore::MAX_VOUCHES_RECEIVED,
+            ErrorCode::TooManyVouchesReceived
+        );
 
-    // 正面互动率加成
-    if trust_score.total_interactions > 0 {
-        let positive_rate = (trust_score.positive_interactions * 100) / trust_score.total_interactions;
-        if positive_rate > 80 {
-            score += 20;
+        let mut penalized: Vec<Pubkey> = Vec::new();
+        for group in vouches {
+            require!(!penalized.contains(group[0].key), ErrorCode::InvalidVouchAccount);
+            penalized.push(group[0].key());
+
+            let vouch = Account::<Vouch>::try_from(&group[0])?;
+            require_keys_eq!(
+                vouch.vouchee,
+                report.reported_user,
+                ErrorCode::InvalidVouchAccount
+            );
+            let expected = Pubkey::create_program_address(
+                &[b"vouch", vouch.voucher.as_ref(), vouch.vouchee.as_ref(), &[vouch.bump]],
+                ctx.program_id,
+            )
+            .map_err(|_| ErrorCode::InvalidVouchAccount)?;
+            require_keys_eq!(expected, group[0].key(), ErrorCode::InvalidVouchAccount);
+
+            // 担保人的 TrustScore 已关闭时（迁移前创建的历史担保）跳过，不能因此阻塞审核
+            let (expected, _) = Pubkey::find_program_address(
+                &[b"trust_score", vouch.voucher.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(expected, group[1].key(), ErrorCode::InvalidVouchAccount);
+            if group[1].data_is_empty() {
+                continue;
+            }
+
+            let mut voucher_score = load_trust_score(&group[1], ctx.program_id)?;
+            let mut voucher_history = load_score_history(&group[2], &vouch.voucher, ctx.program_id)?;
+
+            let new_score = voucher_score
+                .base_score
+                .saturating_sub(scoring_config.params.voucher_penalty);
+            // 记录实际扣分，申诉成立时退还
+            report.voucher_penalties.push(VoucherPenalty {
+                voucher: vouch.voucher,
+                points: voucher_score.base_score - new_score,
+            });
+            voucher_score.apply_score_change(
+                &mut voucher_history,
+                ScoreChange {
+                    new_score,
+                    cause: ScoreCause::VoucherPenalty,
+                    category: Some(engine::Category::Safety),
+                    actor: ctx.accounts.moderator.key(),
+                    config_version: scoring_config.version,
+                    timestamp: clock.unix_timestamp,
+                },
+            );
+
+            emit!(VoucherPenalized {
+                voucher: vouch.voucher,
+                vouchee: vouch.vouchee,
+                report: report.key(),
+                new_score: voucher_score.base_score,
+                config_version: scoring_config.version,
+            });
+
+            voucher_score.exit(ctx.program_id)?;
+            voucher_history.exit(ctx.program_id)?;
         }
+
+        Ok(())
     }
 
-    // 学习连胜加成
-    if trust_score.learning_streak > 7 {
-        score += (trust_score.learning_streak as i32 / 7) * 10;
+    /**
+     * 驳回举报（仅版主）
+     */
+    pub fn dismiss_report(ctx: Context<ResolveReport>) -> Result<()> {
+        let report = &mut ctx.accounts.report;
+        let clock = Clock::get()?;
+
+        require!(report.status == ReportStatus::Pending, ErrorCode::ReportAlreadyResolved);
+
+        report.status = ReportStatus::Dismissed;
+        report.resolved_at = Some(clock.unix_timestamp);
+        report.resolved_by = Some(ctx.accounts.moderator.key());
+
+        let reported_score = &mut ctx.accounts.reported_trust_score;
+        reported_score.pending_reports = reported_score.pending_reports.saturating_sub(1);
+
+        // 举报被驳回，保证金罚没至金库
+        release_bond(
+            &ctx.accounts.bond_vault.to_account_info(),
+            &ctx.accounts.treasury.to_account_info(),
+            report.bond_lamports,
+        )?;
+        report.bond_settled = true;
+
+        msg!("Report dismissed: {}", report.key());
+
+        emit!(ReportResolved {
+            report: report.key(),
+            reporter: report.reporter,
+            reported_user: report.reported_user,
+            moderator: ctx.accounts.moderator.key(),
+            status: report.status,
+            new_score: reported_score.base_score,
+            bond_lamports: report.bond_lamports,
+            config_version: ctx.accounts.scoring_config.version,
+        });
+
+        Ok(())
     }
 
-    // 举报惩罚
-    score -= (trust_score.reports_received as i32) * 25;
+    /**
+     * 举报人在申诉期结束后取回已审核通过举报的保证金
+     */
+    pub fn withdraw_report_bond(ctx: Context<WithdrawReportBond>) -> Result<()> {
+        let report = &mut ctx.accounts.report;
+        let clock = Clock::get()?;
+
+        require!(
+            report.status == ReportStatus::Validated && !report.bond_settled,
+            ErrorCode::BondNotWithdrawable
+        );
+        let resolved_at = report.resolved_at.ok_or(ErrorCode::BondNotWithdrawable)?;
+        require!(
+            clock.unix_timestamp >= resolved_at.saturating_add(Report::APPEAL_WINDOW_SECONDS),
+            ErrorCode::AppealWindowOpen
+        );
 
-    // 限制范围 0-1000
-    Ok(score.clamp(0, 1000) as u16)
-}
+        release_bond(
+            &ctx.accounts.bond_vault.to_account_info(),
+            &ctx.accounts.reporter.to_account_info(),
+            report.bond_lamports,
+        )?;
+        report.bond_settled = true;
 
-fn calculate_weighted_score(trust_score: &TrustScore) -> Result<u16> {
-    let mut weighted = trust_score.base_score as f32;
+        msg!("Report bond withdrawn: {} lamports", report.bond_lamports);
 
-    // 活跃度权重
-    let clock = Clock::get()?;
-    let days_inactive = (clock.unix_timestamp - trust_score.last_active) / 86400;
-    if days_inactive < 7 {
-        weighted *= 1.1;
-    } else if days_inactive > 30 {
-        weighted *= 0.8;
+        Ok(())
     }
 
-    // 互动质量权重
-    if trust_score.total_interactions > 10 {
-        let quality_ratio = trust_score.positive_interactions as f32 / trust_score.total_interactions as f32;
-        weighted *= (0.8 + quality_ratio * 0.4);
+    /**
+     * 被举报人对已审核通过的举报提起申诉（每条举报一次，须在申诉期内，两次申诉间需间隔冷却期）
+     */
+    pub fn file_appeal(ctx: Context<FileAppeal>, statement_hash: [u8; 32]) -> Result<()> {
+        let report = &mut ctx.accounts.report;
+        let trust_score = &mut ctx.accounts.trust_score;
+        let clock = Clock::get()?;
+
+        require!(report.status == ReportStatus::Validated, ErrorCode::ReportNotAppealable);
+        let resolved_at = report.resolved_at.ok_or(ErrorCode::ReportNotAppealable)?;
+        require!(
+            clock.unix_timestamp < resolved_at.saturating_add(Report::APPEAL_WINDOW_SECONDS),
+            ErrorCode::AppealWindowClosed
+        );
+        require!(
+            trust_score.last_appeal_at == 0
+                || clock.unix_timestamp - trust_score.last_appeal_at
+                    >= ctx.accounts.report_config.appeal_cooldown_seconds,
+            ErrorCode::AppealCooldown
+        );
+        trust_score.last_appeal_at = clock.unix_timestamp;
+        // 申诉期间保证金不可取回
+        report.status = ReportStatus::Appealed;
+
+        let appeal = &mut ctx.accounts.appeal;
+        appeal.report = report.key();
+        appeal.appellant = ctx.accounts.appellant.key();
+        appeal.statement_hash = statement_hash;
+        appeal.status = AppealStatus::Pending;
+        appeal.filed_at = clock.unix_timestamp;
+        appeal.resolved_at = None;
+        appeal.resolved_by = None;
+        appeal.bump = ctx.bumps.appeal;
+
+        msg!("Appeal filed against report: {}", report.key());
+
+        emit!(AppealFiled {
+            appeal: appeal.key(),
+            report: report.key(),
+            appellant: appeal.appellant,
+            statement_hash,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
     }
 
-    Ok(weighted.clamp(0.0, 1000.0) as u16)
-}
+    /**
+     * 复核员裁决申诉（不能是原审核员）
+     * 申诉成立时恢复举报扣除的分数并退还担保人的连带扣分，举报标记为已推翻，保证金罚没至金库；
+     * 申诉驳回时举报恢复为审核通过
+     * remaining_accounts（仅申诉成立时）: 按举报记录的担保人顺序成对传入 [担保人 TrustScore, 担保人 ScoreHistory]
+     */
+    pub fn resolve_appeal<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveAppeal<'info>>,
+        overturn: bool,
+    ) -> Result<()> {
+        let appeal = &mut ctx.accounts.appeal;
+        let report = &mut ctx.accounts.report;
+        let clock = Clock::get()?;
 
-// ==========================================
-// 数据结构
-// ==========================================
+        require!(appeal.status == AppealStatus::Pending, ErrorCode::AppealAlreadyResolved);
+        require!(report.status == ReportStatus::Appealed, ErrorCode::ReportNotAppealable);
+        require!(
+            report.resolved_by != Some(ctx.accounts.reviewer.key()),
+            ErrorCode::ReviewerNotIndependent
+        );
 
-#[account]
-pub struct TrustScore {
-    pub owner: Pubkey,
-    pub base_score: u16,
-    pub total_interactions: u32,
-    pub positive_interactions: u32,
-    pub reports_received: u16,
-    pub reports_validated: u16,
-    pub learning_streak: u16,
-    pub last_active: i64,
-    pub bump: u8,
-}
+        appeal.resolved_at = Some(clock.unix_timestamp);
+        appeal.resolved_by = Some(ctx.accounts.reviewer.key());
 
-impl TrustScore {
-    pub const LEN: usize = 8 + 32 + 2 + 4 + 4 + 2 + 2 + 2 + 8 + 1;
-}
+        let reported_score = &mut ctx.accounts.reported_trust_score;
+        let mut restored_points = 0;
+        if overturn {
+            appeal.status = AppealStatus::Granted;
+            report.status = ReportStatus::Overturned;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
-pub enum InteractionType {
-    Match,
-    Chat,
-    HelpfulResponse,
-    SharedContent,
-}
+            let new_score = reported_score
+                .base_score
+                .saturating_add(report.penalty_applied)
+                .min(1000);
+            restored_points = new_score - reported_score.base_score;
+            reported_score.reports_validated = reported_score.reports_validated.saturating_sub(1);
+            reported_score.apply_score_change(
+                &mut ctx.accounts.reported_score_history,
+                ScoreChange {
+                    new_score,
+                    cause: ScoreCause::ReportOverturned,
+                    category: Some(engine::Category::Safety),
+                    actor: ctx.accounts.reviewer.key(),
+                    config_version: ctx.accounts.scoring_config.version,
+                    timestamp: clock.unix_timestamp,
+                },
+            );
 
-// ==========================================
-// 上下文
-// ==========================================
+            // 举报不成立，保证金罚没至金库
+            if !report.bond_settled {
+                release_bond(
+                    &ctx.accounts.bond_vault.to_account_info(),
+                    &ctx.accounts.treasury.to_account_info(),
+                    report.bond_lamports,
+                )?;
+                report.bond_settled = true;
+            }
 
-#[derive(Accounts)]
-pub struct InitializeTrustScore<'info> {
-    #[account(
-        init,
-        payer = owner,
-        space = TrustScore::LEN,
-        seeds = [b"trust_score", owner.key().as_ref()],
-        bump
-    )]
-    pub trust_score: Account<'info, TrustScore>,
-    
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
+            // 退还担保人的连带扣分
+            let vouchers = ctx.remaining_accounts.chunks_exact(2);
+            require!(
+                vouchers.remainder().is_empty() && vouchers.len() == report.voucher_penalties.len(),
+                ErrorCode::InvalidRemainingAccounts
+            );
+            for (penalty, pair) in report.voucher_penalties.iter().zip(vouchers) {
+                // 担保人已迁移钱包时其 TrustScore 已关闭，跳过
+                let (expected, _) = Pubkey::find_program_address(
+                    &[b"trust_score", penalty.voucher.as_ref()],
+                    ctx.program_id,
+                );
+                require_keys_eq!(expected, pair[0].key(), ErrorCode::InvalidTrustScoreAccount);
+                if pair[0].data_is_empty() {
+                    continue;
+                }
 
-#[derive(Accounts)]
-pub struct RecordInteraction<'info> {
-    #[account(
-        mut,
-        seeds = [b"trust_score", owner.key().as_ref()],
-        bump = trust_score.bump,
-    )]
-    pub trust_score: Account<'info, TrustScore>,
-    
-    pub owner: Signer<'info>,
-}
+                let mut voucher_score = load_trust_score(&pair[0], ctx.program_id)?;
+                let mut voucher_history = load_score_history(&pair[1], &penalty.voucher, ctx.program_id)?;
 
-#[derive(Accounts)]
-pub struct RecordLearning<'info> {
-    #[account(
-        mut,
-        seeds = [b"trust_score", owner.key().as_ref()],
-        bump = trust_score.bump,
-    )]
-    pub trust_score: Account<'info, TrustScore>,
-    
-    pub owner: Signer<'info>,
-}
+                let new_score = voucher_score.base_score.saturating_add(penalty.points).min(1000);
+                voucher_score.apply_score_change(
+                    &mut voucher_history,
+                    ScoreChange {
+                        new_score,
+                        cause: ScoreCause::VoucherPenaltyRefunded,
+                        category: Some(engine::Category::Safety),
+                        actor: ctx.accounts.reviewer.key(),
+                        config_version: ctx.accounts.scoring_config.version,
+                        timestamp: clock.unix_timestamp,
+                    },
+                );
 
-#[derive(Accounts)]
-pub struct ReportUser<'info> {
-    #[account(
-        mut,
-        seeds = [b"trust_score", reported_user.key().as_ref()],
-        bump = reported_trust_score.bump,
-    )]
-    pub reported_trust_score: Account<'info, TrustScore>,
-    
-    /// CHECK: 被举报的用户
-    pub reported_user: AccountInfo<'info>,
-    
-    #[account(mut)]
-    pub reporter: Signer<'info>,
-}
+                voucher_score.exit(ctx.program_id)?;
+                voucher_history.exit(ctx.program_id)?;
+            }
+        } else {
+            appeal.status = AppealStatus::Denied;
+            report.status = ReportStatus::Validated;
+        }
 
-#[derive(Accounts)]
-pub struct GetScore<'info> {
-    #[account(
-        seeds = [b"trust_score", user.key().as_ref()],
-        bump = trust_score.bump,
-    )]
-    pub trust_score: Account<'info, TrustScore>,
-    
-    /// CHECK: 查询的用户
-    pub user: AccountInfo<'info>,
-}
+        msg!("Appeal resolved: {:?}", appeal.status);
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Invalid quality score. Must be between 0-100.")]
-    InvalidQualityScore,
-    
-    #[msg("Reason too long. Max 200 characters.")]
-    ReasonTooLong,
-}
+        emit!(AppealResolved {
+            appeal: appeal.key(),
+            report: report.key(),
+            appellant: appeal.appellant,
+            reviewer: ctx.accounts.reviewer.key(),
+            status: appeal.status,
+            restored_points,
+            new_score: reported_score.base_score,
+            config_version: ctx.accounts.scoring_config.version,
+        });
 
-// ==========================================
-// 事件 / Events
-// ==========================================
+        Ok(())
+    }
 
-#[event]
-pub struct TrustScoreInitialized {
-    pub owner: Pubkey,
-    pub initial_score: u16,
-    pub timestamp: i64,
-}
+    /**
+     * 设置账户状态（仅审核员）：正常、暂停至指定时间、永久封禁
+     * 非正常状态的账户不能记录互动与学习，其他程序可据此拒绝建立关系
+     */
+    pub fn set_account_status(ctx: Context<SetAccountStatus>, status: AccountStatus) -> Result<()> {
+        let clock = Clock::get()?;
+        if let AccountStatus::Suspended { until } = status {
+            require!(until > clock.unix_timestamp, ErrorCode::InvalidSuspension);
+        }
 
-#[event]
-pub struct TrustScoreUpdated {
-    pub owner: Pubkey,
-    pub old_score: u16,
-    pub new_score: u16,
-    pub interaction_type: InteractionType,
-    pub timestamp: i64,
-}
+        let trust_score = &mut ctx.accounts.trust_score;
+        trust_score.status = status;
 
-#[event]
-pub struct LearningActivityRecorded {
-    pub owner: Pubkey,
-    pub cards_viewed: u16,
-    pub engagement_score: u8,
-    pub current_streak: u16,
-    pub timestamp: i64,
-}
+        msg!("Account status updated for: {}", trust_score.owner);
 
-#[event]
-pub struct UserReported {
-    pub reporter: Pubkey,
+        emit!(AccountStatusChanged {
+            owner: trust_score.owner,
+            moderator: ctx.accounts.moderator.key(),
+            status,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 获取账户当前状态（通过 return data 返回，已到期的暂停视为正常）
+     */
+    pub fn get_status(ctx: Context<GetStatus>) -> Result<AccountStatus> {
+        let status = ctx.accounts.trust_score.effective_status(Clock::get()?.unix_timestamp);
+        msg!("Account status: {:?}", status);
+        Ok(status)
+    }
+
+    /**
+     * 获取加权信誉分（通过 return data 返回）
+     */
+    pub fn get_weighted_score(ctx: Context<GetScore>) -> Result<u16> {
+        let trust_score = &ctx.accounts.trust_score;
+        let weighted_score = engine::weighted_score(
+            &trust_score.stats(),
+            &ctx.accounts.scoring_config.params.into(),
+            Clock::get()?.unix_timestamp,
+        );
+        
+        msg!("Weighted score: {}", weighted_score);
+        Ok(weighted_score)
+    }
+
+    /**
+     * 获取加权信誉分及置信度（通过 return data 返回）
+     */
+    pub fn get_weighted_score_detailed(ctx: Context<GetScore>) -> Result<WeightedScore> {
+        let trust_score = &ctx.accounts.trust_score;
+        let estimate = engine::weighted_estimate(
+            &trust_score.stats(),
+            &ctx.accounts.scoring_config.params.into(),
+            Clock::get()?.unix_timestamp,
+        );
+
+        msg!("Weighted score: {} (confidence {} bps)", estimate.score, estimate.confidence_bps);
+        Ok(estimate.into())
+    }
+
+    /**
+     * 签发信誉证书：将当前加权分快照到 ScoreCertificate，到期 slot 后失效
+     * 重复签发会覆盖旧证书
+     */
+    pub fn issue_score_certificate(
+        ctx: Context<IssueScoreCertificate>,
+        validity_slots: u64,
+    ) -> Result<()> {
+        require!(
+            validity_slots > 0 && validity_slots <= ScoreCertificate::MAX_VALIDITY_SLOTS,
+            ErrorCode::InvalidCertificateValidity
+        );
+
+        let trust_score = &ctx.accounts.trust_score;
+        let clock = Clock::get()?;
+        require!(trust_score.is_active(clock.unix_timestamp), ErrorCode::AccountSuspended);
+
+        let estimate = engine::weighted_estimate(
+            &trust_score.stats(),
+            &ctx.accounts.scoring_config.params.into(),
+            clock.unix_timestamp,
+        );
+
+        let certificate = &mut ctx.accounts.certificate;
+        certificate.owner = trust_score.owner;
+        certificate.weighted_score = estimate.score;
+        certificate.confidence_bps = estimate.confidence_bps;
+        certificate.issued_at = clock.unix_timestamp;
+        certificate.issued_slot = clock.slot;
+        certificate.expiry_slot = clock.slot + validity_slots;
+        certificate.config_version = ctx.accounts.scoring_config.version;
+        certificate.bump = ctx.bumps.certificate;
+
+        msg!(
+            "Score certificate issued: {} (expires at slot {})",
+            certificate.weighted_score,
+            certificate.expiry_slot
+        );
+
+        emit!(ScoreCertificateIssued {
+            owner: certificate.owner,
+            weighted_score: certificate.weighted_score,
+            confidence_bps: certificate.confidence_bps,
+            expiry_slot: certificate.expiry_slot,
+            config_version: certificate.config_version,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 校验信誉证书（供其他程序 CPI 调用）
+     * 证书过期、分数低于 min_score 或账户当前非正常状态时返回错误，否则通过 return data 返回证书分数
+     */
+    pub fn verify_certificate(ctx: Context<VerifyCertificate>, min_score: u16) -> Result<u16> {
+        let certificate = &ctx.accounts.certificate;
+        let clock = Clock::get()?;
+
+        require!(
+            clock.slot <= certificate.expiry_slot,
+            ErrorCode::CertificateExpired
+        );
+        // 签发后被暂停或封禁的账户，证书随之失效
+        require!(
+            ctx.accounts.trust_score.is_active(clock.unix_timestamp),
+            ErrorCode::AccountSuspended
+        );
+        require!(
+            certificate.weighted_score >= min_score,
+            ErrorCode::ScoreBelowMinimum
+        );
+
+        Ok(certificate.weighted_score)
+    }
+
+    /**
+     * 应用不活跃衰减（无需许可，任何人都可以调用）
+     */
+    pub fn apply_decay(ctx: Context<ApplyDecay>) -> Result<()> {
+        let scoring_config = &ctx.accounts.scoring_config;
+        let clock = Clock::get()?;
+
+        apply_trust_score_decay(
+            &mut ctx.accounts.trust_score,
+            &mut ctx.accounts.score_history,
+            scoring_config,
+            clock.unix_timestamp,
+        );
+
+        Ok(())
+    }
+
+    /**
+     * 批量应用不活跃衰减
+     * remaining_accounts: 成对传入 [TrustScore, ScoreHistory, ...]（均可写）
+     */
+    pub fn apply_decay_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ApplyDecayBatch<'info>>,
+    ) -> Result<()> {
+        let scoring_config = &ctx.accounts.scoring_config;
+        let clock = Clock::get()?;
+
+        let pairs = ctx.remaining_accounts.chunks_exact(2);
+        require!(pairs.remainder().is_empty(), ErrorCode::InvalidRemainingAccounts);
+
+        for pair in pairs {
+            let mut trust_score = load_trust_score(&pair[0], ctx.program_id)?;
+            let mut score_history = load_score_history(&pair[1], &trust_score.owner, ctx.program_id)?;
+
+            apply_trust_score_decay(
+                &mut trust_score,
+                &mut score_history,
+                scoring_config,
+                clock.unix_timestamp,
+            );
+            trust_score.exit(ctx.program_id)?;
+            score_history.exit(ctx.program_id)?;
+        }
+
+        msg!("Decay applied to {} accounts", ctx.remaining_accounts.len() / 2);
+
+        Ok(())
+    }
+
+    /**
+     * 批量记录互动（API 中继使用，证明方共同签名），整批原子执行
+     * Match / Chat 条目必须指定互动对方，并校验 social-graph 中有效的 MatchEdge，同一对方的重复互动加分递减；
+     * HelpfulResponse / SharedContent 条目不指定对方
+     * remaining_accounts: 按 entries 顺序依次传入 [TrustScore, ScoreHistory]（均可写），
+     * Match / Chat 条目之后紧跟 [MatchEdge, InteractionPair]（InteractionPair 可写，尚未创建时由证明方支付租金）
+     */
+    pub fn record_interactions_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RecordInteractionsBatch<'info>>,
+        entries: Vec<InteractionEntry>,
+    ) -> Result<()> {
+        require!(
+            !entries.is_empty() && entries.len() <= InteractionEntry::MAX_BATCH,
+            ErrorCode::InvalidBatchSize
+        );
+
+        let scoring_config = &ctx.accounts.scoring_config;
+        let params: engine::Params = scoring_config.params.into();
+        let attester = ctx.accounts.attester.key();
+        let clock = Clock::get()?;
+
+        let mut accounts = ctx.remaining_accounts.iter();
+        let mut results: Vec<InteractionBatchResult> = Vec::with_capacity(entries.len());
+        for entry in entries.iter() {
+            require!(entry.quality_score <= 100, ErrorCode::InvalidQualityScore);
+            require!(!entry.interaction_type.is_negative(), ErrorCode::InvalidInteractionType);
+            let counterparty = if entry.interaction_type.requires_match() {
+                Some(entry.counterparty.ok_or(ErrorCode::MatchEdgeRequired)?)
+            } else {
+                require!(entry.counterparty.is_none(), ErrorCode::InvalidInteractionType);
+                None
+            };
+            // 同一账户在批内重复出现时，后写入会覆盖前一次更新
+            require!(
+                !results.iter().any(|result| result.owner == entry.owner),
+                ErrorCode::DuplicateBatchEntry
+            );
+
+            let (Some(score_info), Some(history_info)) = (accounts.next(), accounts.next()) else {
+                return err!(ErrorCode::InvalidRemainingAccounts);
+            };
+            let mut trust_score = load_trust_score(score_info, ctx.program_id)?;
+            require_keys_eq!(trust_score.owner, entry.owner, ErrorCode::InvalidTrustScoreAccount);
+            require!(trust_score.is_active(clock.unix_timestamp), ErrorCode::AccountSuspended);
+            let mut score_history = load_score_history(history_info, &entry.owner, ctx.program_id)?;
+
+            trust_score.attestation_nonce += 1;
+            trust_score.total_interactions += 1;
+            if engine::is_positive(&params, entry.interaction_type.into(), entry.quality_score) {
+                trust_score.positive_interactions += 1;
+            }
+
+            // 批量互动没有评分人，按评分人权重下限计入
+            let mut new_score = engine::scale_adjustment(
+                trust_score.base_score,
+                engine::interaction_score(
+                    &trust_score.stats(),
+                    &params,
+                    entry.interaction_type.into(),
+                    entry.quality_score,
+                    clock.unix_timestamp,
+                ),
+                engine::unrated_weight_bps(&params),
+            );
+
+            if let Some(counterparty) = counterparty {
+                require!(counterparty != entry.owner, ErrorCode::SelfInteraction);
+                let (Some(match_edge), Some(pair_info)) = (accounts.next(), accounts.next()) else {
+                    return err!(ErrorCode::InvalidRemainingAccounts);
+                };
+                verify_match_edge(match_edge, &entry.owner, &counterparty)?;
+
+                let mut interaction_pair = load_or_create_interaction_pair(
+                    pair_info,
+                    &entry.owner,
+                    &counterparty,
+                    &ctx.accounts.attester,
+                    &ctx.accounts.system_program,
+                    ctx.program_id,
+                    clock.unix_timestamp,
+                )?;
+                new_score = apply_pair_window(
+                    &mut interaction_pair,
+                    trust_score.base_score,
+                    new_score,
+                    &params,
+                    clock.unix_timestamp,
+                );
+                interaction_pair.exit(ctx.program_id)?;
+            }
+
+            trust_score.last_active = clock.unix_timestamp;
+            let old_score = trust_score.apply_score_change(
+                &mut score_history,
+                ScoreChange {
+                    new_score,
+                    cause: ScoreCause::Interaction,
+                    category: Some(engine::InteractionKind::from(entry.interaction_type).category()),
+                    actor: attester,
+                    config_version: scoring_config.version,
+                    timestamp: clock.unix_timestamp,
+                },
+            );
+            trust_score.exit(ctx.program_id)?;
+            score_history.exit(ctx.program_id)?;
+
+            results.push(InteractionBatchResult {
+                owner: entry.owner,
+                interaction_type: entry.interaction_type,
+                old_score,
+                new_score,
+                counterparty,
+            });
+        }
+        require!(accounts.next().is_none(), ErrorCode::InvalidRemainingAccounts);
+
+        msg!("Batch recorded {} interactions", results.len());
+
+        emit!(InteractionsBatchRecorded {
+            attester,
+            results,
+            timestamp: clock.unix_timestamp,
+            config_version: scoring_config.version,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 纪元结算：发布链下计算的 (owner, score, epoch) Merkle 根（仅证明方）
+     * 纪元号必须递增，snapshot_at 为链下读取分数的时间；用户凭证明调用 claim_epoch_score 结算
+     */
+    pub fn publish_epoch_root(
+        ctx: Context<PublishEpochRoot>,
+        epoch: u64,
+        root: [u8; 32],
+        leaf_count: u32,
+        snapshot_at: i64,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(
+            epoch > 0 && leaf_count > 0 && snapshot_at <= clock.unix_timestamp,
+            ErrorCode::InvalidEpochRoot
+        );
+
+        let epoch_state = &mut ctx.accounts.epoch_state;
+        require!(epoch > epoch_state.latest_epoch, ErrorCode::EpochNotIncreasing);
+        epoch_state.latest_epoch = epoch;
+        epoch_state.bump = ctx.bumps.epoch_state;
+
+        let epoch_root = &mut ctx.accounts.epoch_root;
+        epoch_root.epoch = epoch;
+        epoch_root.root = root;
+        epoch_root.leaf_count = leaf_count;
+        epoch_root.publisher = ctx.accounts.attester.key();
+        epoch_root.published_at = clock.unix_timestamp;
+        epoch_root.config_version = ctx.accounts.scoring_config.version;
+        epoch_root.bump = ctx.bumps.epoch_root;
+        epoch_root.snapshot_at = snapshot_at;
+
+        msg!("Epoch {} root published with {} leaves", epoch, leaf_count);
+
+        emit!(EpochRootPublished {
+            epoch,
+            root,
+            leaf_count,
+            publisher: epoch_root.publisher,
+            snapshot_at,
+            timestamp: clock.unix_timestamp,
+            config_version: epoch_root.config_version,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 按纪元 Merkle 根结算 TrustScore（任何人可代为提交）
+     * 叶子为 (owner, score, epoch)，只能结算最新发布的纪元；
+     * 快照之后分数有过变动、或账户非正常状态时拒绝结算，变化量按比例同步到所有分项
+     */
+    pub fn claim_epoch_score(
+        ctx: Context<ClaimEpochScore>,
+        score: u16,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let epoch_root = &ctx.accounts.epoch_root;
+        let trust_score = &mut ctx.accounts.trust_score;
+        let clock = Clock::get()?;
+
+        require!(score <= engine::MAX_SCORE, ErrorCode::InvalidMerkleProof);
+        require!(
+            epoch_root.epoch == ctx.accounts.epoch_state.latest_epoch,
+            ErrorCode::EpochNotLatest
+        );
+        require!(
+            epoch_root.epoch > trust_score.last_settled_epoch,
+            ErrorCode::StaleEpoch
+        );
+        require!(trust_score.is_active(clock.unix_timestamp), ErrorCode::AccountSuspended);
+        // 快照之后的链上变动不在链下分数中，直接覆盖会丢失
+        let score_history = &mut ctx.accounts.score_history;
+        require!(
+            !matches!(score_history.latest(), Some(snapshot) if snapshot.timestamp > epoch_root.snapshot_at),
+            ErrorCode::ScoreChangedSinceSnapshot
+        );
+
+        let leaf = merkle::ScoreLeaf {
+            owner: trust_score.owner.to_bytes(),
+            score,
+            epoch: epoch_root.epoch,
+        };
+        require!(
+            merkle::verify::<Sha256Syscall>(
+                &epoch_root.root,
+                leaf.hash::<Sha256Syscall>(),
+                &proof
+            ),
+            ErrorCode::InvalidMerkleProof
+        );
+
+        trust_score.last_settled_epoch = epoch_root.epoch;
+        // 链下只提交总分，变化量同步到所有分项
+        let mut sub_scores: engine::SubScores = trust_score.sub_scores.into();
+        sub_scores.shift_all(trust_score.base_score, score);
+        trust_score.sub_scores = sub_scores.into();
+        let old_score = trust_score.apply_score_change(
+            score_history,
+            ScoreChange {
+                new_score: score,
+                cause: ScoreCause::EpochSettlement,
+                category: None,
+                actor: ctx.accounts.claimer.key(),
+                config_version: epoch_root.config_version,
+                timestamp: clock.unix_timestamp,
+            },
+        );
+
+        msg!("Epoch {} settled: {} -> {}", epoch_root.epoch, old_score, score);
+
+        emit!(EpochScoreClaimed {
+            owner: trust_score.owner,
+            epoch: epoch_root.epoch,
+            old_score,
+            new_score: score,
+            claimer: ctx.accounts.claimer.key(),
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 将旧布局的 TrustScore 扩容到当前大小（补齐分项信誉分）
+     * 新增字段按零初始化，分项信誉分为空时取当前 base_score
+     */
+    pub fn upgrade_trust_score_layout(ctx: Context<UpgradeTrustScoreLayout>) -> Result<()> {
+        let account_info = ctx.accounts.trust_score.to_account_info();
+        require!(account_info.data_len() < TrustScore::LEN, ErrorCode::AlreadyUpgraded);
+        require!(
+            account_info.try_borrow_data()?.starts_with(TrustScore::DISCRIMINATOR),
+            ErrorCode::InvalidTrustScoreAccount
+        );
+
+        resize_with_rent(
+            &account_info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program,
+            TrustScore::LEN,
+        )?;
+
+        let mut trust_score = TrustScore::try_deserialize(&mut &account_info.try_borrow_data()?[..])?;
+        let expected = Pubkey::create_program_address(
+            &[b"trust_score", trust_score.owner.as_ref(), &[trust_score.bump]],
+            ctx.program_id,
+        )
+        .map_err(|_| ErrorCode::InvalidTrustScoreAccount)?;
+        require_keys_eq!(expected, account_info.key(), ErrorCode::InvalidTrustScoreAccount);
+
+        // 重复升级（布局再次扩展）时保留已有分项
+        if trust_score.sub_scores == SubScores::default() {
+            trust_score.sub_scores = engine::SubScores::uniform(trust_score.base_score).into();
+        }
+        trust_score.try_serialize(&mut &mut account_info.try_borrow_mut_data()?[..])?;
+
+        msg!("Trust score layout upgraded for: {}", trust_score.owner);
+
+        Ok(())
+    }
+
+    /**
+     * 将旧布局的 ScoringConfig 扩容到当前大小（仅管理员）
+     * 新增的评分参数取默认值，已有参数保持不变，版本号递增
+     */
+    pub fn upgrade_scoring_config(ctx: Context<UpgradeScoringConfig>) -> Result<()> {
+        let account_info = ctx.accounts.scoring_config.to_account_info();
+        require!(account_info.data_len() < ScoringConfig::LEN, ErrorCode::AlreadyUpgraded);
+
+        // 旧布局：discriminator || admin || version || params（较短） || bump
+        let (admin, version, params) = {
+            let data = account_info.try_borrow_data()?;
+            require!(
+                data.starts_with(ScoringConfig::DISCRIMINATOR) && data.len() > 8 + 32 + 4 + 1,
+                ErrorCode::InvalidScoringConfigAccount
+            );
+            let admin = Pubkey::try_from(&data[8..40]).map_err(|_| ErrorCode::InvalidScoringConfigAccount)?;
+            let version = u32::from_le_bytes([data[40], data[41], data[42], data[43]]);
+
+            // 新增参数追加在末尾，旧参数字节覆盖默认参数的前缀
+            let old_params = &data[44..data.len() - 1];
+            let mut params_bytes = Vec::with_capacity(ScoringParams::LEN);
+            ScoringParams::default().serialize(&mut params_bytes)?;
+            params_bytes[..old_params.len()].copy_from_slice(old_params);
+            let params = ScoringParams::try_from_slice(&params_bytes)
+                .map_err(|_| ErrorCode::InvalidScoringConfigAccount)?;
+
+            (admin, version, params)
+        };
+        require_keys_eq!(admin, ctx.accounts.admin.key(), ErrorCode::Unauthorized);
+        params.validate()?;
+
+        resize_with_rent(
+            &account_info,
+            &ctx.accounts.admin.to_account_info(),
+            &ctx.accounts.system_program,
+            ScoringConfig::LEN,
+        )?;
+
+        let scoring_config = ScoringConfig {
+            admin,
+            version: version + 1,
+            params,
+            bump: ctx.bumps.scoring_config,
+        };
+        scoring_config.try_serialize(&mut &mut account_info.try_borrow_mut_data()?[..])?;
+
+        msg!("Scoring config layout upgraded. Version: {}", scoring_config.version);
+
+        emit!(ScoringConfigUpdated {
+            admin,
+            version: scoring_config.version,
+            params,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 为已有的 TrustScore 补建分数历史账户
+     */
+    pub fn initialize_score_history(ctx: Context<InitializeScoreHistory>) -> Result<()> {
+        let trust_score = &ctx.accounts.trust_score;
+        let score_history = &mut ctx.accounts.score_history;
+        let clock = Clock::get()?;
+
+        score_history.owner = trust_score.owner;
+        score_history.bump = ctx.bumps.score_history;
+        score_history.push(clock.unix_timestamp, trust_score.base_score, ScoreCause::Initialized);
+
+        msg!("Score history initialized for: {}", trust_score.owner);
+
+        Ok(())
+    }
+
+    /**
+     * 为其他用户担保
+     * 担保权重由担保人当前 base_score 决定，立即计入被担保人的分数
+     * 担保人与被担保人都必须处于正常状态
+     */
+    pub fn vouch_for(ctx: Context<VouchFor>) -> Result<()> {
+        let voucher_score = &mut ctx.accounts.voucher_trust_score;
+        let vouchee_score = &mut ctx.accounts.vouchee_trust_score;
+        let scoring_config = &ctx.accounts.scoring_config;
+        let params = &scoring_config.params;
+        let clock = Clock::get()?;
+
+        require!(
+            ctx.accounts.voucher.key() != ctx.accounts.vouchee.key(),
+            ErrorCode::SelfVouch
+        );
+        require!(
+            voucher_score.is_active(clock.unix_timestamp)
+                && vouchee_score.is_active(clock.unix_timestamp),
+            ErrorCode::AccountSuspended
+        );
+        require!(
+            voucher_score.base_score >= params.min_voucher_score,
+            ErrorCode::VoucherScoreTooLow
+        );
+        require!(
+            voucher_score.outstanding_vouches < params.max_outstanding_vouches,
+            ErrorCode::TooManyVouches
+        );
+        require!(
+            (vouchee_score.vouches_received as usize) < TrustScore::MAX_VOUCHES_RECEIVED,
+            ErrorCode::TooManyVouchesReceived
+        );
+
+        let weight = engine::vouch_weight(voucher_score.base_score, &(*params).into());
+
+        let vouch = &mut ctx.accounts.vouch;
+        vouch.voucher = ctx.accounts.voucher.key();
+        vouch.vouchee = ctx.accounts.vouchee.key();
+        vouch.weight = weight;
+        vouch.created_at = clock.unix_timestamp;
+        vouch.bump = ctx.bumps.vouch;
+
+        voucher_score.outstanding_vouches += 1;
+        vouchee_score.vouches_received += 1;
+        let new_score = engine::cap_probation_gain(
+            &vouchee_score.stats(),
+            &(*params).into(),
+            vouchee_score.base_score.saturating_add(weight).min(1000),
+            clock.unix_timestamp,
+        );
+        vouchee_score.apply_score_change(
+            &mut ctx.accounts.vouchee_score_history,
+            ScoreChange {
+                new_score,
+                cause: ScoreCause::Vouch,
+                category: Some(engine::Category::Social),
+                actor: ctx.accounts.voucher.key(),
+                config_version: scoring_config.version,
+                timestamp: clock.unix_timestamp,
+            },
+        );
+
+        msg!("Vouch created: {} -> {} (weight {})", vouch.voucher, vouch.vouchee, weight);
+
+        emit!(VouchCreated {
+            voucher: vouch.voucher,
+            vouchee: vouch.vouchee,
+            weight,
+            new_score: vouchee_score.base_score,
+            config_version: scoring_config.version,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 撤销担保，收回当时计入的权重
+     */
+    pub fn revoke_vouch(ctx: Context<RevokeVouch>) -> Result<()> {
+        let vouch = &ctx.accounts.vouch;
+        let voucher_score = &mut ctx.accounts.voucher_trust_score;
+        let vouchee_score = &mut ctx.accounts.vouchee_trust_score;
+        let clock = Clock::get()?;
+
+        voucher_score.outstanding_vouches = voucher_score.outstanding_vouches.saturating_sub(1);
+        vouchee_score.vouches_received = vouchee_score.vouches_received.saturating_sub(1);
+        let new_score = vouchee_score.base_score.saturating_sub(vouch.weight);
+        vouchee_score.apply_score_change(
+            &mut ctx.accounts.vouchee_score_history,
+            ScoreChange {
+                new_score,
+                cause: ScoreCause::VouchRevoked,
+                category: Some(engine::Category::Social),
+                actor: ctx.accounts.voucher.key(),
+                config_version: ctx.accounts.scoring_config.version,
+                timestamp: clock.unix_timestamp,
+            },
+        );
+
+        msg!("Vouch revoked: {} -> {}", vouch.voucher, vouch.vouchee);
+
+        emit!(VouchRevoked {
+            voucher: vouch.voucher,
+            vouchee: vouch.vouchee,
+            weight: vouch.weight,
+            new_score: vouchee_score.base_score,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 钱包轮换：由旧钱包签名，将信誉迁移到新钱包
+     * 有待审核的举报时不能迁移（举报按旧钱包索引）
+     * remaining_accounts: 旧钱包发出和收到的全部担保，按 [旧 Vouch, 新 Vouch PDA] 成对传入，随信誉一并迁移
+     */
+    pub fn migrate_trust_score<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateTrustScore<'info>>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let accounts = &mut *ctx.accounts;
+        require!(
+            accounts.old_trust_score.pending_reports == 0,
+            ErrorCode::PendingReports
+        );
+
+        rekey_vouches(
+            ctx.remaining_accounts,
+            &accounts.old_owner.key(),
+            &accounts.new_owner.key(),
+            accounts.old_trust_score.outstanding_vouches as usize
+                + accounts.old_trust_score.vouches_received as usize,
+            &accounts.system_program,
+            ctx.program_id,
+        )?;
+
+        migrate_reputation(
+            &mut accounts.old_trust_score,
+            &mut accounts.old_score_history,
+            &mut accounts.new_trust_score,
+            &mut accounts.new_score_history,
+            accounts.new_owner.key(),
+            ctx.bumps.new_trust_score,
+            ctx.bumps.new_score_history,
+            accounts.old_owner.key(),
+            accounts.scoring_config.version,
+            clock.unix_timestamp,
+        );
+
+        msg!(
+            "Trust score migrated: {} -> {}",
+            accounts.old_owner.key(),
+            accounts.new_owner.key()
+        );
+
+        emit!(TrustScoreMigrated {
+            old_owner: accounts.old_owner.key(),
+            new_owner: accounts.new_owner.key(),
+            base_score: accounts.new_trust_score.base_score,
+            via_recovery: false,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 从 trust-score-mini 的 TrustAccount 迁移到完整 TrustScore（任何人可代为提交）
+     * mini 的配置与分数没有可信来源，分数最多按 INITIAL_SCORE 计入，只保留扣分；
+     * 尚无 TrustScore 的用户新建账户，已有账户的用户将 mini 扣分合并到现有分数。
+     * mini 计数不区分正负面互动，互动计数不迁移。
+     * 迁移后通过 CPI 关闭 mini 账户，租金退还给用户
+     */
+    pub fn migrate_from_mini(ctx: Context<MigrateFromMini>) -> Result<()> {
+        let clock = Clock::get()?;
+        let accounts = &mut *ctx.accounts;
+
+        let mini = MiniTrustAccountView::try_deserialize(
+            &accounts.mini_trust_account.try_borrow_data()?,
+        )?;
+        require_keys_eq!(mini.owner, accounts.user.key(), ErrorCode::InvalidMiniAccount);
+        let mini_score = mini.score.min(TrustScore::INITIAL_SCORE as u64) as u16;
+
+        let trust_score = &mut accounts.trust_score;
+        let score_history = &mut accounts.score_history;
+        let merged = trust_score.owner != Pubkey::default();
+
+        // 合并时已有的 TrustScore 也可能没有 ScoreHistory（早于分数历史创建的账户），两种情况都要初始化
+        if score_history.owner == Pubkey::default() {
+            score_history.owner = mini.owner;
+            score_history.bump = ctx.bumps.score_history;
+        }
+
+        let migrated_score = if merged {
+            let penalty = TrustScore::INITIAL_SCORE - mini_score;
+            let merged_score = trust_score.base_score.saturating_sub(penalty);
+            let mut sub_scores: engine::SubScores = trust_score.sub_scores.into();
+            sub_scores.shift_all(trust_score.base_score, merged_score);
+            trust_score.sub_scores = sub_scores.into();
+            merged_score
+        } else {
+            trust_score.owner = mini.owner;
+            trust_score.base_score = 0;
+            trust_score.last_active = clock.unix_timestamp;
+            trust_score.bump = ctx.bumps.trust_score;
+            trust_score.last_decay_at = clock.unix_timestamp;
+            trust_score.created_at = clock.unix_timestamp;
+            trust_score.sub_scores = engine::SubScores::uniform(mini_score).into();
+            mini_score
+        };
+
+        trust_score.apply_score_change(
+            score_history,
+            ScoreChange {
+                new_score: migrated_score,
+                cause: ScoreCause::Migrated,
+                category: None,
+                actor: accounts.payer.key(),
+                config_version: accounts.scoring_config.version,
+                timestamp: clock.unix_timestamp,
+            },
+        );
+
+        // 由本程序的 migrator PDA 签名，调用 mini 的 close
+        let close = Instruction {
+            program_id: TRUST_SCORE_MINI_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(accounts.mini_trust_account.key(), false),
+                AccountMeta::new(accounts.user.key(), false),
+                AccountMeta::new_readonly(accounts.mini_migrator.key(), true),
+            ],
+            data: MiniTrustAccountView::CLOSE_DISCRIMINATOR.to_vec(),
+        };
+        invoke_signed(
+            &close,
+            &[
+                accounts.mini_trust_account.to_account_info(),
+                accounts.user.to_account_info(),
+                accounts.mini_migrator.to_account_info(),
+                accounts.mini_program.to_account_info(),
+            ],
+            &[&[b"mini_migrator", &[ctx.bumps.mini_migrator]]],
+        )?;
+
+        msg!("Trust score migrated from mini: {} ({} -> {})", mini.owner, mini.score, migrated_score);
+
+        emit!(TrustScoreMigratedFromMini {
+            owner: mini.owner,
+            mini_score: mini.score,
+            mini_total_interactions: mini.total_interactions,
+            base_score: migrated_score,
+            timestamp: clock.unix_timestamp,
+            merged,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 设置社交恢复监护人
+     * 监护人达到阈值并经过时间锁后，可将信誉迁移到新钱包
+     */
+    pub fn configure_guardians(
+        ctx: Context<ConfigureGuardians>,
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+        timelock_seconds: i64,
+    ) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+
+        require!(
+            !guardians.is_empty() && guardians.len() <= GuardianSet::MAX_GUARDIANS,
+            ErrorCode::InvalidGuardianConfig
+        );
+        require!(
+            threshold > 0 && threshold as usize <= guardians.len(),
+            ErrorCode::InvalidGuardianConfig
+        );
+        require!(
+            timelock_seconds >= GuardianSet::MIN_TIMELOCK_SECONDS,
+            ErrorCode::InvalidGuardianConfig
+        );
+        for (i, guardian) in guardians.iter().enumerate() {
+            require!(
+                *guardian != owner && !guardians[..i].contains(guardian),
+                ErrorCode::InvalidGuardianConfig
+            );
+        }
+
+        let guardian_set = &mut ctx.accounts.guardian_set;
+        guardian_set.owner = owner;
+        guardian_set.guardians = guardians;
+        guardian_set.threshold = threshold;
+        guardian_set.timelock_seconds = timelock_seconds;
+        guardian_set.bump = ctx.bumps.guardian_set;
+
+        msg!(
+            "Guardians configured: {} of {}",
+            threshold,
+            guardian_set.guardians.len()
+        );
+
+        Ok(())
+    }
+
+    /**
+     * 监护人发起恢复请求
+     */
+    pub fn initiate_recovery(ctx: Context<InitiateRecovery>, new_owner: Pubkey) -> Result<()> {
+        let guardian_set = &ctx.accounts.guardian_set;
+        let guardian = ctx.accounts.guardian.key();
+        let clock = Clock::get()?;
+
+        require!(new_owner != guardian_set.owner, ErrorCode::InvalidRecoveryTarget);
+
+        let executable_at = clock
+            .unix_timestamp
+            .checked_add(guardian_set.timelock_seconds)
+            .ok_or(ErrorCode::InvalidGuardianConfig)?;
+
+        let recovery = &mut ctx.accounts.recovery_request;
+        recovery.old_owner = guardian_set.owner;
+        recovery.new_owner = new_owner;
+        recovery.initiator = guardian;
+        recovery.approvals = vec![guardian];
+        recovery.initiated_at = clock.unix_timestamp;
+        recovery.bump = ctx.bumps.recovery_request;
+        recovery.vetoes = Vec::new();
+
+        msg!("Recovery initiated for {} -> {}", recovery.old_owner, new_owner);
+
+        emit!(RecoveryInitiated {
+            old_owner: recovery.old_owner,
+            new_owner,
+            guardian,
+            executable_at,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 监护人批准恢复请求
+     */
+    pub fn approve_recovery(ctx: Context<ApproveRecovery>) -> Result<()> {
+        let recovery = &mut ctx.accounts.recovery_request;
+        let guardian = ctx.accounts.guardian.key();
+
+        require!(
+            !recovery.approvals.contains(&guardian),
+            ErrorCode::RecoveryAlreadyApproved
+        );
+        recovery.approvals.push(guardian);
+
+        msg!("Recovery approved by: {}", guardian);
+
+        emit!(RecoveryApproved {
+            old_owner: recovery.old_owner,
+            guardian,
+            approvals: recovery.approvals.len() as u8,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 原钱包取消恢复请求（密钥仍在手时）
+     */
+    pub fn cancel_recovery(ctx: Context<CancelRecovery>) -> Result<()> {
+        msg!("Recovery cancelled for: {}", ctx.accounts.old_owner.key());
+        Ok(())
+    }
+
+    /**
+     * 监护人否决恢复请求（原钱包丢失时，防止单个监护人的恶意请求永久占用）
+     * 否决数达到阈值后关闭请求，监护人可重新发起
+     */
+    pub fn veto_recovery(ctx: Context<VetoRecovery>) -> Result<()> {
+        let guardian_set = &ctx.accounts.guardian_set;
+        let recovery = &mut ctx.accounts.recovery_request;
+        let guardian = ctx.accounts.guardian.key();
+
+        require!(
+            !recovery.vetoes.contains(&guardian),
+            ErrorCode::RecoveryAlreadyVetoed
+        );
+        recovery.vetoes.push(guardian);
+
+        // 仅统计当前仍是监护人的否决
+        let vetoes = recovery
+            .vetoes
+            .iter()
+            .filter(|key| guardian_set.guardians.contains(key))
+            .count();
+
+        msg!("Recovery vetoed by: {} ({} of {})", guardian, vetoes, guardian_set.threshold);
+
+        emit!(RecoveryVetoed {
+            old_owner: recovery.old_owner,
+            guardian,
+            vetoes: vetoes as u8,
+        });
+
+        if vetoes >= guardian_set.threshold as usize {
+            recovery.close(ctx.accounts.initiator.to_account_info())?;
+            msg!("Recovery cancelled by guardians for: {}", guardian_set.owner);
+        }
+
+        Ok(())
+    }
+
+    /**
+     * 执行恢复：监护人批准数达到阈值且时间锁到期后，由新钱包执行迁移
+     * remaining_accounts: 同 migrate_trust_score，旧钱包的全部担保按 [旧 Vouch, 新 Vouch PDA] 成对传入
+     */
+    pub fn execute_recovery<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteRecovery<'info>>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let accounts = &mut *ctx.accounts;
+        let guardian_set = &accounts.guardian_set;
+        let recovery = &accounts.recovery_request;
+
+        // 仅统计当前仍是监护人的批准
+        let approvals = recovery
+            .approvals
+            .iter()
+            .filter(|key| guardian_set.guardians.contains(key))
+            .count();
+        require!(
+            approvals >= guardian_set.threshold as usize,
+            ErrorCode::RecoveryThresholdNotMet
+        );
+        let executable_at = recovery
+            .initiated_at
+            .checked_add(guardian_set.timelock_seconds)
+            .ok_or(ErrorCode::InvalidGuardianConfig)?;
+        require!(
+            clock.unix_timestamp >= executable_at,
+            ErrorCode::RecoveryTimelockActive
+        );
+        require!(
+            accounts.old_trust_score.pending_reports == 0,
+            ErrorCode::PendingReports
+        );
+
+        rekey_vouches(
+            ctx.remaining_accounts,
+            &recovery.old_owner,
+            &accounts.new_owner.key(),
+            accounts.old_trust_score.outstanding_vouches as usize
+                + accounts.old_trust_score.vouches_received as usize,
+            &accounts.system_program,
+            ctx.program_id,
+        )?;
+
+        migrate_reputation(
+            &mut accounts.old_trust_score,
+            &mut accounts.old_score_history,
+            &mut accounts.new_trust_score,
+            &mut accounts.new_score_history,
+            accounts.new_owner.key(),
+            ctx.bumps.new_trust_score,
+            ctx.bumps.new_score_history,
+            accounts.new_owner.key(),
+            accounts.scoring_config.version,
+            clock.unix_timestamp,
+        );
+
+        msg!(
+            "Trust score recovered: {} -> {}",
+            recovery.old_owner,
+            recovery.new_owner
+        );
+
+        emit!(TrustScoreMigrated {
+            old_owner: recovery.old_owner,
+            new_owner: recovery.new_owner,
+            base_score: accounts.new_trust_score.base_score,
+            via_recovery: true,
+            timestamp: clock.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 初始化评分参数配置（使用默认参数，版本号从 1 开始），仅程序升级权限可调用
+     */
+    pub fn initialize_scoring_config(ctx: Context<InitializeScoringConfig>) -> Result<()> {
+        let scoring_config = &mut ctx.accounts.scoring_config;
+
+        scoring_config.admin = ctx.accounts.admin.key();
+        scoring_config.version = 1;
+        scoring_config.params = ScoringParams::default();
+        scoring_config.bump = ctx.bumps.scoring_config;
+
+        msg!("Scoring config initialized. Version: {}", scoring_config.version);
+
+        Ok(())
+    }
+
+    /**
+     * 更新评分参数（版本号递增）
+     */
+    pub fn update_scoring_config(
+        ctx: Context<UpdateScoringConfig>,
+        params: ScoringParams,
+    ) -> Result<()> {
+        params.validate()?;
+
+        let scoring_config = &mut ctx.accounts.scoring_config;
+        scoring_config.params = params;
+        scoring_config.version += 1;
+
+        msg!("Scoring config updated. Version: {}", scoring_config.version);
+
+        emit!(ScoringConfigUpdated {
+            admin: ctx.accounts.admin.key(),
+            version: scoring_config.version,
+            params,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 初始化举报配置（保证金金额、金库）及保证金托管账户，仅程序升级权限可调用
+     */
+    pub fn initialize_report_config(
+        ctx: Context<InitializeReportConfig>,
+        bond_lamports: u64,
+        treasury: Pubkey,
+        appeal_cooldown_seconds: i64,
+    ) -> Result<()> {
+        require!(appeal_cooldown_seconds >= 0, ErrorCode::InvalidAppealCooldown);
+        let report_config = &mut ctx.accounts.report_config;
+
+        report_config.admin = ctx.accounts.admin.key();
+        report_config.bond_lamports = bond_lamports;
+        report_config.treasury = treasury;
+        report_config.appeal_cooldown_seconds = appeal_cooldown_seconds;
+        report_config.bump = ctx.bumps.report_config;
+
+        ctx.accounts.bond_vault.bump = ctx.bumps.bond_vault;
+
+        msg!("Report config initialized. Bond: {} lamports", bond_lamports);
+
+        Ok(())
+    }
+
+    /**
+     * 更新举报保证金金额、金库地址及申诉冷却期
+     */
+    pub fn update_report_config(
+        ctx: Context<UpdateReportConfig>,
+        bond_lamports: u64,
+        treasury: Pubkey,
+        appeal_cooldown_seconds: i64,
+    ) -> Result<()> {
+        require!(appeal_cooldown_seconds >= 0, ErrorCode::InvalidAppealCooldown);
+        let report_config = &mut ctx.accounts.report_config;
+
+        report_config.bond_lamports = bond_lamports;
+        report_config.treasury = treasury;
+        report_config.appeal_cooldown_seconds = appeal_cooldown_seconds;
+
+        msg!("Report config updated. Bond: {} lamports", bond_lamports);
+
+        Ok(())
+    }
+
+    /**
+     * 初始化授权方注册表（证明方等），仅程序升级权限可调用
+     */
+    pub fn initialize_authority_registry(
+        ctx: Context<InitializeAuthorityRegistry>,
+        role: AuthorityRole,
+    ) -> Result<()> {
+        let registry = &mut ctx.accounts.authority_registry;
+
+        registry.admin = ctx.accounts.admin.key();
+        registry.role = role;
+        registry.authorities = Vec::new();
+        registry.enforced = false;
+        registry.bump = ctx.bumps.authority_registry;
+
+        msg!("Authority registry initialized. Admin: {}", registry.admin);
+
+        Ok(())
+    }
+
+    /**
+     * 添加授权方
+     */
+    pub fn add_authority(ctx: Context<UpdateAuthorityRegistry>, authority: Pubkey) -> Result<()> {
+        let registry = &mut ctx.accounts.authority_registry;
+
+        require!(
+            !registry.authorities.contains(&authority),
+            ErrorCode::AuthorityAlreadyRegistered
+        );
+        require!(
+            registry.authorities.len() < AuthorityRegistry::MAX_AUTHORITIES,
+            ErrorCode::AuthorityRegistryFull
+        );
+
+        registry.authorities.push(authority);
+
+        msg!("Authority added: {}", authority);
+
+        emit!(AuthorityRegistryUpdated {
+            role: registry.role,
+            authority,
+            added: true,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 移除授权方
+     */
+    pub fn remove_authority(ctx: Context<UpdateAuthorityRegistry>, authority: Pubkey) -> Result<()> {
+        let registry = &mut ctx.accounts.authority_registry;
+
+        let index = registry
+            .authorities
+            .iter()
+            .position(|key| *key == authority)
+            .ok_or(ErrorCode::AuthorityNotFound)?;
+        registry.authorities.swap_remove(index);
+
+        msg!("Authority removed: {}", authority);
+
+        emit!(AuthorityRegistryUpdated {
+            role: registry.role,
+            authority,
+            added: false,
+        });
+
+        Ok(())
+    }
+
+    /**
+     * 开启/关闭强制模式
+     * 证明方注册表开启后，record_interaction / record_learning_activity 必须附带证明
+     */
+    pub fn set_authority_enforcement(
+        ctx: Context<UpdateAuthorityRegistry>,
+        enforced: bool,
+    ) -> Result<()> {
+        let registry = &mut ctx.accounts.authority_registry;
+        registry.enforced = enforced;
+
+        msg!("Authority enforcement set to: {}", enforced);
+
+        Ok(())
+    }
+}
+
+// ==========================================
+// 辅助函数
+// ==========================================
+
+/// 校验证明：证明方共同签名，或前一条 ed25519 指令验证过的签名载荷
+/// 返回完成校验的证明方（未开启强制且非必需时为 None）
+fn verify_attestation(
+    registry: &AuthorityRegistry,
+    attester: Option<&Signer>,
+    instructions_sysvar: Option<&UncheckedAccount>,
+    message: &[u8],
+    required: bool,
+) -> Result<Option<Pubkey>> {
+    if !registry.enforced && !required {
+        return Ok(None);
+    }
+
+    if let Some(attester) = attester {
+        require!(
+            registry.authorities.contains(attester.key),
+            ErrorCode::UnauthorizedAttester
+        );
+        return Ok(Some(attester.key()));
+    }
+
+    let instructions_sysvar = instructions_sysvar.ok_or(ErrorCode::AttestationRequired)?;
+    let signer = verify_ed25519_instruction(instructions_sysvar, message)?;
+    require!(
+        registry.authorities.contains(&signer),
+        ErrorCode::UnauthorizedAttester
+    );
+
+    Ok(Some(signer))
+}
+
+/// 通过指令自省读取紧邻的 ed25519 验签指令，确认其签名的正是 `message`
+/// 返回签名公钥
+fn verify_ed25519_instruction(instructions_sysvar: &AccountInfo, message: &[u8]) -> Result<Pubkey> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, ErrorCode::AttestationRequired);
+
+    let ix = load_instruction_at_checked((current_index - 1) as usize, instructions_sysvar)?;
+    require!(
+        ix.program_id == solana_sdk_ids::ed25519_program::ID,
+        ErrorCode::AttestationRequired
+    );
+
+    // 数据布局：[签名数量, 填充, 偏移量结构 (7 x u16), ...]
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, ErrorCode::InvalidAttestation);
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let public_key_offset = read_u16(6) as usize;
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+
+    // 所有数据必须位于 ed25519 指令本身
+    require!(
+        read_u16(4) == u16::MAX && read_u16(8) == u16::MAX && read_u16(14) == u16::MAX,
+        ErrorCode::InvalidAttestation
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(ErrorCode::InvalidAttestation)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::InvalidAttestation)?;
+    require!(signed_message == message, ErrorCode::InvalidAttestation);
+
+    Ok(Pubkey::try_from(public_key).map_err(|_| ErrorCode::InvalidAttestation)?)
+}
+
+/// 按半衰期衰减 base_score
+/// 只有分数实际下降时才推进 last_decay_at，反复调用既不能加速衰减也不能冻结衰减
+fn apply_trust_score_decay(
+    trust_score: &mut TrustScore,
+    score_history: &mut ScoreHistory,
+    scoring_config: &ScoringConfig,
+    now: i64,
+) {
+    let params: engine::Params = scoring_config.params.into();
+    let Some((new_score, sub_scores)) = engine::decay_step(&trust_score.stats(), &params, now) else {
+        return;
+    };
+    let old_score = trust_score.base_score;
+
+    // 分项按同一半衰期衰减，不再按 base_score 的变化量平移
+    trust_score.sub_scores = sub_scores.into();
+    trust_score.last_decay_at = now;
+
+    // 衰减无需许可，actor 记为本程序
+    trust_score.apply_score_change(
+        score_history,
+        ScoreChange {
+            new_score,
+            cause: ScoreCause::Decay,
+            category: None,
+            actor: crate::ID,
+            config_version: scoring_config.version,
+            timestamp: now,
+        },
+    );
+
+    msg!("Trust score decayed: {} -> {}", old_score, new_score);
+
+    emit!(TrustScoreDecayed {
+        owner: trust_score.owner,
+        old_score,
+        new_score,
+        timestamp: now,
+        config_version: scoring_config.version,
+    });
+}
+
+/// 将旧账户的信誉与分数历史复制到新 owner 的 PDA（旧账户由上下文 close）
+/// 旧账户记为降到 0、新账户记为从 0 升到原分数，两条 ScoreChanged 均为 Migrated
+#[allow(clippy::too_many_arguments)]
+fn migrate_reputation(
+    old_trust_score: &mut TrustScore,
+    old_score_history: &mut ScoreHistory,
+    new_trust_score: &mut TrustScore,
+    new_score_history: &mut ScoreHistory,
+    new_owner: Pubkey,
+    trust_score_bump: u8,
+    score_history_bump: u8,
+    actor: Pubkey,
+    config_version: u32,
+    now: i64,
+) {
+    let score = old_trust_score.base_score;
+
+    new_trust_score.clone_from(old_trust_score);
+    new_trust_score.owner = new_owner;
+    new_trust_score.bump = trust_score_bump;
+    new_trust_score.attestation_nonce = 0;
+    new_trust_score.base_score = 0;
+
+    new_score_history.clone_from(old_score_history);
+    new_score_history.owner = new_owner;
+    new_score_history.bump = score_history_bump;
+
+    let migrated = |new_score| ScoreChange {
+        new_score,
+        cause: ScoreCause::Migrated,
+        category: None,
+        actor,
+        config_version,
+        timestamp: now,
+    };
+    old_trust_score.apply_score_change(old_score_history, migrated(0));
+    new_trust_score.apply_score_change(new_score_history, migrated(score));
+}
+
+/// 将旧钱包发出和收到的全部担保迁移到新钱包：关闭旧 Vouch PDA，按新钱包重新派生并写入
+/// 旧账户的租金直接转入新账户，无需额外付款
+/// `accounts` 为 [旧 Vouch, 新 Vouch PDA] 成对传入，组数必须等于迁移前的担保数之和
+fn rekey_vouches<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    old_owner: &Pubkey,
+    new_owner: &Pubkey,
+    vouch_count: usize,
+    system_program: &Program<'info, System>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let pairs = accounts.chunks_exact(2);
+    require!(pairs.remainder().is_empty(), ErrorCode::InvalidRemainingAccounts);
+    // 必须迁移全部担保，否则留在旧钱包名下的 Vouch 无法撤销，也无法在审核举报时传入
+    require!(pairs.len() == vouch_count, ErrorCode::IncompleteVouchAccounts);
+
+    let mut migrated: Vec<Pubkey> = Vec::new();
+    for pair in pairs {
+        require!(!migrated.contains(pair[0].key), ErrorCode::InvalidVouchAccount);
+        migrated.push(pair[0].key());
+        require!(pair[1].is_writable, ErrorCode::AccountNotWritable);
+
+        let vouch = Account::<Vouch>::try_from(&pair[0])?;
+        let expected = Pubkey::create_program_address(
+            &[b"vouch", vouch.voucher.as_ref(), vouch.vouchee.as_ref(), &[vouch.bump]],
+            program_id,
+        )
+        .map_err(|_| ErrorCode::InvalidVouchAccount)?;
+        require_keys_eq!(expected, pair[0].key(), ErrorCode::InvalidVouchAccount);
+
+        let rekey = |key: Pubkey| if key == *old_owner { *new_owner } else { key };
+        let (voucher, vouchee) = (rekey(vouch.voucher), rekey(vouch.vouchee));
+        require!(
+            voucher != vouch.voucher || vouchee != vouch.vouchee,
+            ErrorCode::InvalidVouchAccount
+        );
+        let (expected, bump) = Pubkey::find_program_address(
+            &[b"vouch", voucher.as_ref(), vouchee.as_ref()],
+            program_id,
+        );
+        require_keys_eq!(expected, pair[1].key(), ErrorCode::InvalidVouchAccount);
+
+        // 关闭旧账户，租金转入新账户
+        let lamports = pair[0].lamports();
+        **pair[0].try_borrow_mut_lamports()? = 0;
+        **pair[1].try_borrow_mut_lamports()? += lamports;
+        pair[0].assign(&system_program::ID);
+        pair[0].resize(0)?;
+
+        let signer_seeds: &[&[u8]] = &[b"vouch", voucher.as_ref(), vouchee.as_ref(), &[bump]];
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::Allocate {
+                    account_to_allocate: pair[1].clone(),
+                },
+                &[signer_seeds],
+            ),
+            Vouch::LEN as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::Assign {
+                    account_to_assign: pair[1].clone(),
+                },
+                &[signer_seeds],
+            ),
+            program_id,
+        )?;
+
+        let rekeyed = Vouch {
+            voucher,
+            vouchee,
+            weight: vouch.weight,
+            created_at: vouch.created_at,
+            bump,
+        };
+        rekeyed.try_serialize(&mut &mut pair[1].try_borrow_mut_data()?[..])?;
+    }
+
+    Ok(())
+}
+
+/// 将程序所有的账户扩容到 `len`，由 payer 补足租金
+fn resize_with_rent<'info>(
+    account_info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    len: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(len);
+    let top_up = rent.saturating_sub(account_info.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account_info.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    account_info.resize(len)?;
+
+    Ok(())
+}
+
+/// 评分人权重：评分人 PDA（已由调用方校验）尚未创建时取 rater_floor_bps
+fn rater_weight_bps(rater_trust_score: &AccountInfo, params: &engine::Params, now: i64) -> Result<u64> {
+    if rater_trust_score.data_is_empty() {
+        return Ok(engine::unrated_weight_bps(params));
+    }
+
+    require_keys_eq!(*rater_trust_score.owner, crate::ID, ErrorCode::InvalidTrustScoreAccount);
+    let rater = TrustScore::try_deserialize(&mut &rater_trust_score.try_borrow_data()?[..])?;
+    let rater_score = engine::weighted_score(&rater.stats(), params, now);
+    Ok(engine::rater_weight_bps(rater_score, params))
+}
+
+/// 同一对方的重复互动加分递减：推进滚动窗口计数，并按窗口内已有次数缩放本次加分
+fn apply_pair_window(
+    interaction_pair: &mut InteractionPair,
+    old_score: u16,
+    new_score: u16,
+    params: &engine::Params,
+    now: i64,
+) -> u16 {
+    let (window_start, window_count) = engine::roll_pair_window(
+        interaction_pair.window_start,
+        interaction_pair.window_count,
+        params,
+        now,
+    );
+    let multiplier = engine::pair_multiplier_bps(window_count, params);
+
+    interaction_pair.window_start = window_start;
+    interaction_pair.window_count = window_count.saturating_add(1);
+    interaction_pair.total_count = interaction_pair.total_count.saturating_add(1);
+
+    engine::scale_gain(old_score, new_score, multiplier)
+}
+
+/// 从 remaining_accounts 加载 InteractionPair 并校验 PDA，尚未创建时由 payer 支付租金创建
+fn load_or_create_interaction_pair<'info>(
+    account_info: &'info AccountInfo<'info>,
+    owner: &Pubkey,
+    counterparty: &Pubkey,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    program_id: &Pubkey,
+    now: i64,
+) -> Result<Account<'info, InteractionPair>> {
+    let (expected, bump) = Pubkey::find_program_address(
+        &[b"interaction_pair", owner.as_ref(), counterparty.as_ref()],
+        program_id,
+    );
+    require_keys_eq!(expected, account_info.key(), ErrorCode::InvalidInteractionPairAccount);
+    require!(account_info.is_writable, ErrorCode::AccountNotWritable);
+
+    if account_info.data_is_empty() {
+        // 与 init 相同：补足租金后分配空间并转交本程序（账户可能已被预先转入 lamports）
+        let rent = Rent::get()?.minimum_balance(InteractionPair::LEN);
+        let top_up = rent.saturating_sub(account_info.lamports());
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: payer.to_account_info(),
+                        to: account_info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+
+        let signer_seeds: &[&[u8]] =
+            &[b"interaction_pair", owner.as_ref(), counterparty.as_ref(), &[bump]];
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::Allocate {
+                    account_to_allocate: account_info.clone(),
+                },
+                &[signer_seeds],
+            ),
+            InteractionPair::LEN as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::Assign {
+                    account_to_assign: account_info.clone(),
+                },
+                &[signer_seeds],
+            ),
+            program_id,
+        )?;
+
+        let interaction_pair = InteractionPair {
+            owner: *owner,
+            counterparty: *counterparty,
+            window_start: now,
+            window_count: 0,
+            total_count: 0,
+            bump,
+        };
+        interaction_pair.try_serialize(&mut &mut account_info.try_borrow_mut_data()?[..])?;
+    }
+
+    Account::try_from(account_info)
+}
+
+/// 从 remaining_accounts 加载 TrustScore 并校验 PDA
+fn load_trust_score<'info>(
+    account_info: &'info AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<Account<'info, TrustScore>> {
+    require!(account_info.is_writable, ErrorCode::AccountNotWritable);
+
+    let trust_score = Account::<TrustScore>::try_from(account_info)?;
+    let expected = Pubkey::create_program_address(
+        &[b"trust_score", trust_score.owner.as_ref(), &[trust_score.bump]],
+        program_id,
+    )
+    .map_err(|_| ErrorCode::InvalidTrustScoreAccount)?;
+    require_keys_eq!(expected, account_info.key(), ErrorCode::InvalidTrustScoreAccount);
+
+    Ok(trust_score)
+}
+
+/// 从 remaining_accounts 加载 owner 的 ScoreHistory 并校验 PDA
+fn load_score_history<'info>(
+    account_info: &'info AccountInfo<'info>,
+    owner: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<Box<Account<'info, ScoreHistory>>> {
+    require!(account_info.is_writable, ErrorCode::AccountNotWritable);
+
+    let score_history = Box::new(Account::<ScoreHistory>::try_from(account_info)?);
+    let expected = Pubkey::create_program_address(
+        &[b"score_history", owner.as_ref(), &[score_history.bump]],
+        program_id,
+    )
+    .map_err(|_| ErrorCode::InvalidScoreHistoryAccount)?;
+    require_keys_eq!(expected, account_info.key(), ErrorCode::InvalidScoreHistoryAccount);
+
+    Ok(score_history)
+}
+
+/// 纪元 Merkle 证明使用的 sha256（链上走 syscall）
+struct Sha256Syscall;
+
+impl merkle::MerkleHasher for Sha256Syscall {
+    fn hashv(parts: &[&[u8]]) -> merkle::Hash {
+        solana_sha256_hasher::hashv(parts).to_bytes()
+    }
+}
+
+/// 校验 social-graph 的 MatchEdge：账户归属、PDA、双方身份、双方确认及激活状态
+fn verify_match_edge(match_edge: &AccountInfo, owner: &Pubkey, counterparty: &Pubkey) -> Result<()> {
+    require_keys_eq!(
+        *match_edge.owner,
+        SOCIAL_GRAPH_PROGRAM_ID,
+        ErrorCode::InvalidMatchEdge
+    );
+
+    let edge = MatchEdgeView::try_deserialize(&match_edge.try_borrow_data()?)?;
+    let (user_a, user_b) = if owner < counterparty {
+        (owner, counterparty)
+    } else {
+        (counterparty, owner)
+    };
+    require!(
+        edge.user_a == *user_a && edge.user_b == *user_b,
+        ErrorCode::InvalidMatchEdge
+    );
+
+    let expected = Pubkey::create_program_address(
+        &[b"match_edge", user_a.as_ref(), user_b.as_ref(), &[edge.bump]],
+        &SOCIAL_GRAPH_PROGRAM_ID,
+    )
+    .map_err(|_| ErrorCode::InvalidMatchEdge)?;
+    require_keys_eq!(expected, match_edge.key(), ErrorCode::InvalidMatchEdge);
+
+    require!(edge.is_active, ErrorCode::MatchNotActive);
+    require!(edge.accepted_at.is_some(), ErrorCode::MatchNotAccepted);
+
+    Ok(())
+}
+
+/// 从保证金托管账户（程序所有）划出 lamports
+fn release_bond(bond_vault: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let vault_balance = bond_vault.lamports();
+    **bond_vault.try_borrow_mut_lamports()? = vault_balance
+        .checked_sub(amount)
+        .ok_or(ErrorCode::InsufficientBondVault)?;
+    **to.try_borrow_mut_lamports()? += amount;
+
+    Ok(())
+}
+
+/// 互动证明载荷：前缀 || owner || 对方（无则全零） || 类型 || 质量分 || nonce
+fn interaction_attestation_message(
+    owner: &Pubkey,
+    counterparty: Option<&Pubkey>,
+    interaction_type: InteractionType,
+    quality_score: u8,
+    nonce: u64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(96);
+    message.extend_from_slice(b"swiv:record_interaction");
+    message.extend_from_slice(owner.as_ref());
+    message.extend_from_slice(counterparty.unwrap_or(&Pubkey::default()).as_ref());
+    message.push(interaction_type as u8);
+    message.push(quality_score);
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message
+}
+
+/// 学习证明载荷：前缀 || owner || 卡片数 || 参与度 || nonce
+fn learning_attestation_message(
+    owner: &Pubkey,
+    cards_viewed: u16,
+    engagement_score: u8,
+    nonce: u64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(64);
+    message.extend_from_slice(b"swiv:record_learning");
+    message.extend_from_slice(owner.as_ref());
+    message.extend_from_slice(&cards_viewed.to_le_bytes());
+    message.push(engagement_score);
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message
+}
+
+// ==========================================
+// 数据结构
+// ==========================================
+
+#[account]
+pub struct TrustScore {
+    pub owner: Pubkey,
+    pub base_score: u16,
+    pub total_interactions: u32,
+    pub positive_interactions: u32,
+    pub reports_received: u16,
+    pub reports_validated: u16,
+    pub learning_streak: u16,
+    pub last_active: i64,
+    pub bump: u8,
+    /// 证明载荷 nonce，防止重放
+    pub attestation_nonce: u64,
+    /// 上次应用不活跃衰减的时间
+    pub last_decay_at: i64,
+    /// 作为担保人的有效担保数
+    pub outstanding_vouches: u16,
+    /// 收到的有效担保数
+    pub vouches_received: u16,
+    /// 分项信誉分（旧账户需先调用 upgrade_trust_score_layout）
+    pub sub_scores: SubScores,
+    /// 创建时间（观察期起点，旧账户升级后为 0，即不受观察期限制）
+    pub created_at: i64,
+    /// 审核状态
+    pub status: AccountStatus,
+    /// 上次提起申诉的时间
+    pub last_appeal_at: i64,
+    /// 最近一次结算的纪元
+    pub last_settled_epoch: u64,
+    /// 待审核的举报数（不为 0 时不能迁移钱包）
+    pub pending_reports: u16,
+}
+
+impl TrustScore {
+    /// 新账户的初始分数
+    pub const INITIAL_SCORE: u16 = 100;
+    /// 可收到的担保数上限（审核举报时须在一笔交易内传入全部担保）
+    pub const MAX_VOUCHES_RECEIVED: usize = 8;
+
+    pub const LEN: usize = 8 + 32 + 2 + 4 + 4 + 2 + 2 + 2 + 8 + 1 + 8 + 8 + 2 + 2 + SubScores::LEN + 8 + AccountStatus::LEN + 8 + 8 + 2;
+
+    /// 考虑暂停到期后的当前状态
+    pub fn effective_status(&self, now: i64) -> AccountStatus {
+        match self.status {
+            AccountStatus::Suspended { until } if until <= now => AccountStatus::Active,
+            status => status,
+        }
+    }
+
+    /// 是否可以记录互动、建立关系
+    pub fn is_active(&self, now: i64) -> bool {
+        self.effective_status(now) == AccountStatus::Active
+    }
+
+    /// 分数变动的唯一入口：更新 base_score 与分项、写入分数历史并发出 ScoreChanged
+    /// 返回变动前的分数
+    pub fn apply_score_change(&mut self, score_history: &mut ScoreHistory, change: ScoreChange) -> u16 {
+        let old_score = self.base_score;
+
+        if let Some(category) = change.category {
+            let mut sub_scores: engine::SubScores = self.sub_scores.into();
+            sub_scores.shift(category, old_score, change.new_score);
+            self.sub_scores = sub_scores.into();
+        }
+        self.base_score = change.new_score;
+
+        score_history.push(change.timestamp, change.new_score, change.cause);
+
+        emit!(ScoreChanged {
+            owner: self.owner,
+            old: old_score,
+            new: change.new_score,
+            cause: change.cause,
+            actor: change.actor,
+            config_version: change.config_version,
+            timestamp: change.timestamp,
+        });
+
+        old_score
+    }
+
+    /// 评分引擎所需的状态快照
+    pub fn stats(&self) -> engine::Stats {
+        engine::Stats {
+            base_score: self.base_score,
+            total_interactions: self.total_interactions,
+            positive_interactions: self.positive_interactions,
+            reports_validated: self.reports_validated,
+            learning_streak: self.learning_streak,
+            last_active: self.last_active,
+            last_decay_at: self.last_decay_at,
+            sub_scores: self.sub_scores.into(),
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// 一次分数变动
+pub struct ScoreChange {
+    pub new_score: u16,
+    pub cause: ScoreCause,
+    /// 变化量计入的分项；None 表示不平移分项（初始化、迁移、衰减、纪元结算由调用方处理）
+    pub category: Option<engine::Category>,
+    /// 触发变动的账户（签名者、证明方或审核员）
+    pub actor: Pubkey,
+    pub config_version: u32,
+    pub timestamp: i64,
+}
+
+/// 账户审核状态
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AccountStatus {
+    #[default]
+    Active,
+    /// 暂停至指定时间（Unix 时间戳）
+    Suspended { until: i64 },
+    Banned,
+}
+
+impl AccountStatus {
+    pub const LEN: usize = 1 + 8;
+}
+
+/// 分项信誉分：社交、学习、指导、安全
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubScores {
+    pub social: u16,
+    pub learning: u16,
+    pub mentoring: u16,
+    pub safety: u16,
+}
+
+impl SubScores {
+    pub const LEN: usize = 2 * 4;
+}
+
+impl From<engine::SubScores> for SubScores {
+    fn from(sub_scores: engine::SubScores) -> Self {
+        Self {
+            social: sub_scores.social,
+            learning: sub_scores.learning,
+            mentoring: sub_scores.mentoring,
+            safety: sub_scores.safety,
+        }
+    }
+}
+
+impl From<SubScores> for engine::SubScores {
+    fn from(sub_scores: SubScores) -> Self {
+        Self {
+            social: sub_scores.social,
+            learning: sub_scores.learning,
+            mentoring: sub_scores.mentoring,
+            safety: sub_scores.safety,
+        }
+    }
+}
+
+/// 分数历史（环形缓冲区，保留最近 CAPACITY 条快照）
+#[account]
+pub struct ScoreHistory {
+    pub owner: Pubkey,
+    /// 下一条写入位置
+    pub head: u16,
+    /// 已写入条数（不超过 CAPACITY）
+    pub len: u16,
+    pub entries: [ScoreSnapshot; 64],
+    pub bump: u8,
+}
+
+impl ScoreHistory {
+    pub const CAPACITY: usize = 64;
+    pub const LEN: usize = 8 + 32 + 2 + 2 + ScoreSnapshot::LEN * Self::CAPACITY + 1;
+
+    /// 追加一条快照；连续的衰减记录合并为一条，避免无许可 crank 刷掉历史
+    pub fn push(&mut self, timestamp: i64, base_score: u16, cause: ScoreCause) {
+        let snapshot = ScoreSnapshot {
+            timestamp,
+            base_score,
+            cause,
+        };
+
+        if cause == ScoreCause::Decay && self.len > 0 {
+            let latest = (self.head as usize + Self::CAPACITY - 1) % Self::CAPACITY;
+            if self.entries[latest].cause == ScoreCause::Decay {
+                self.entries[latest] = snapshot;
+                return;
+            }
+        }
+
+        self.entries[self.head as usize] = snapshot;
+        self.head = ((self.head as usize + 1) % Self::CAPACITY) as u16;
+        self.len = (self.len + 1).min(Self::CAPACITY as u16);
+    }
+
+    /// 最近一条快照
+    pub fn latest(&self) -> Option<&ScoreSnapshot> {
+        if self.len == 0 {
+            return None;
+        }
+        Some(&self.entries[(self.head as usize + Self::CAPACITY - 1) % Self::CAPACITY])
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct ScoreSnapshot {
+    pub timestamp: i64,
+    pub base_score: u16,
+    pub cause: ScoreCause,
+}
+
+impl ScoreSnapshot {
+    pub const LEN: usize = 8 + 2 + 1;
+}
+
+/// 分数变动原因
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScoreCause {
+    #[default]
+    Initialized,
+    Interaction,
+    Learning,
+    ReportFiled,
+    ReportValidated,
+    Decay,
+    Migrated,
+    Vouch,
+    VouchRevoked,
+    VoucherPenalty,
+    NegativeInteraction,
+    ReportOverturned,
+    EpochSettlement,
+    VoucherPenaltyRefunded,
+}
+
+/// 与同一对方的互动计数（滚动窗口），用于递减重复互动的加分
+#[account]
+pub struct InteractionPair {
+    pub owner: Pubkey,
+    pub counterparty: Pubkey,
+    pub window_start: i64,
+    pub window_count: u16,
+    pub total_count: u32,
+    pub bump: u8,
+}
+
+impl InteractionPair {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 2 + 4 + 1;
+}
+
+/// 担保关系（担保人 -> 被担保人）
+#[account]
+pub struct Vouch {
+    pub voucher: Pubkey,
+    pub vouchee: Pubkey,
+    /// 创建时计入被担保人分数的权重
+    pub weight: u16,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Vouch {
+    pub const LEN: usize = 8 + 32 + 32 + 2 + 8 + 1;
+}
+
+/// 社交恢复监护人配置
+#[account]
+pub struct GuardianSet {
+    pub owner: Pubkey,
+    pub guardians: Vec<Pubkey>,
+    /// 执行恢复所需的最少监护人批准数
+    pub threshold: u8,
+    /// 发起恢复到可执行之间的等待时间
+    pub timelock_seconds: i64,
+    pub bump: u8,
+}
+
+impl GuardianSet {
+    pub const MAX_GUARDIANS: usize = 5;
+    pub const MIN_TIMELOCK_SECONDS: i64 = 86_400;
+    pub const LEN: usize = 8 + 32 + (4 + 32 * Self::MAX_GUARDIANS) + 1 + 8 + 1;
+}
+
+/// 待执行的恢复请求
+#[account]
+pub struct RecoveryRequest {
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+    /// 发起的监护人（请求关闭时退还租金）
+    pub initiator: Pubkey,
+    pub approvals: Vec<Pubkey>,
+    pub initiated_at: i64,
+    pub bump: u8,
+    /// 否决该请求的监护人
+    pub vetoes: Vec<Pubkey>,
+}
+
+impl RecoveryRequest {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + (4 + 32 * GuardianSet::MAX_GUARDIANS) + 8 + 1 + (4 + 32 * GuardianSet::MAX_GUARDIANS);
+}
+
+/// 授权方注册表（每个角色一个 PDA）
+#[account]
+pub struct AuthorityRegistry {
+    pub admin: Pubkey,
+    pub role: AuthorityRole,
+    pub authorities: Vec<Pubkey>,
+    /// 是否强制要求该角色参与（证明方：record_* 必须附带证明）
+    pub enforced: bool,
+    pub bump: u8,
+}
+
+impl AuthorityRegistry {
+    pub const MAX_AUTHORITIES: usize = 10;
+    pub const LEN: usize = 8 + 32 + 1 + (4 + 32 * Self::MAX_AUTHORITIES) + 1 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityRole {
+    /// 互动/学习记录的证明方（API 服务、预言机）
+    Attester,
+    /// 举报审核版主
+    Moderator,
+    /// 申诉复核员（独立于版主）
+    Reviewer,
+}
+
+impl AuthorityRole {
+    pub fn seed(&self) -> &'static [u8] {
+        match self {
+            AuthorityRole::Attester => b"attester",
+            AuthorityRole::Moderator => b"moderator",
+            AuthorityRole::Reviewer => b"reviewer",
+        }
+    }
+}
+
+/// 举报记录（每个举报人对每个被举报人一条）
+#[account]
+pub struct Report {
+    pub reporter: Pubkey,
+    pub reported_user: Pubkey,
+    pub reason: String,
+    pub evidence_hash: [u8; 32],
+    pub status: ReportStatus,
+    pub created_at: i64,
+    pub resolved_at: Option<i64>,
+    pub resolved_by: Option<Pubkey>,
+    /// 托管的举报保证金
+    pub bond_lamports: u64,
+    pub bump: u8,
+    /// 审核通过时实际扣除的分数（申诉成立时恢复）
+    pub penalty_applied: u16,
+    /// 审核通过时受到连带扣分的担保人及实际扣分（申诉成立时退还）
+    pub voucher_penalties: Vec<VoucherPenalty>,
+    /// 保证金是否已结算（退还或罚没）
+    pub bond_settled: bool,
+}
+
+impl Report {
+    pub const LEN: usize = 8 + 32 + 32 + (4 + 200) + 32 + 1 + 8 + 9 + 33 + 8 + 1 + 2 + (4 + VoucherPenalty::LEN * TrustScore::MAX_VOUCHES_RECEIVED) + 1;
+    /// 审核通过后可提起申诉的期限，期满后举报人才能取回保证金
+    pub const APPEAL_WINDOW_SECONDS: i64 = 7 * 86_400;
+}
+
+/// 担保人的连带扣分记录
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct VoucherPenalty {
+    pub voucher: Pubkey,
+    pub points: u16,
+}
+
+impl VoucherPenalty {
+    pub const LEN: usize = 32 + 2;
+}
+
+/// 对已审核举报的申诉（每条举报一条）
+#[account]
+pub struct Appeal {
+    pub report: Pubkey,
+    pub appellant: Pubkey,
+    /// 申诉陈述的哈希（原文存储在链下）
+    pub statement_hash: [u8; 32],
+    pub status: AppealStatus,
+    pub filed_at: i64,
+    pub resolved_at: Option<i64>,
+    pub resolved_by: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl Appeal {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 1 + 8 + 9 + 33 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AppealStatus {
+    Pending,  // 待复核
+    Granted,  // 申诉成立
+    Denied,   // 申诉驳回
+}
+
+/// 评分参数配置（管理员可调，版本号随每次更新递增）
+#[account]
+pub struct ScoringConfig {
+    pub admin: Pubkey,
+    pub version: u32,
+    pub params: ScoringParams,
+    pub bump: u8,
+}
+
+impl ScoringConfig {
+    pub const LEN: usize = 8 + 32 + 4 + ScoringParams::LEN + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ScoringParams {
+    /// 各互动类型的质量分权重
+    pub match_weight: u8,
+    pub chat_weight: u8,
+    pub helpful_response_weight: u8,
+    pub shared_content_weight: u8,
+    /// 计为正面互动的质量分下限
+    pub positive_threshold: u8,
+    /// 正面互动率（%）超过该值时加分
+    pub positive_rate_bonus_threshold: u8,
+    pub positive_rate_bonus: u16,
+    /// 学习连胜：每满 N 天加分
+    pub streak_bonus_days: u16,
+    pub streak_bonus_points: u16,
+    /// 学习活跃加分
+    pub learning_engagement_threshold: u8,
+    pub learning_bonus: u16,
+    /// 每条已审核举报在计算时的扣分
+    pub report_penalty: u16,
+    /// 举报审核通过时的即时扣分
+    pub validation_penalty: u16,
+    /// 活跃度乘数（基点，10000 = 1.0）
+    pub active_days: u16,
+    pub active_multiplier_bps: u16,
+    pub inactive_days: u16,
+    pub inactive_multiplier_bps: u16,
+    /// 互动质量乘数 = base + 正面率 * range（基点）
+    pub quality_min_interactions: u32,
+    pub quality_base_bps: u16,
+    pub quality_range_bps: u16,
+    /// 不活跃衰减半衰期（天，0 表示关闭）与衰减下限
+    pub decay_half_life_days: u16,
+    pub decay_floor: u16,
+    /// 担保权重（基点）及上限、担保人门槛、担保数上限、连带扣分
+    pub vouch_weight_bps: u16,
+    pub max_vouch_weight: u16,
+    pub min_voucher_score: u16,
+    pub max_outstanding_vouches: u16,
+    pub voucher_penalty: u16,
+    /// 同一对方的计数窗口（秒）、不打折次数、超出后的递减系数（基点）
+    pub pair_window_seconds: u32,
+    pub pair_free_interactions: u16,
+    pub pair_decay_bps: u16,
+    /// 负面行为扣分：爽约、垃圾信息、骚扰、诈骗
+    pub no_show_severity: u16,
+    pub spam_severity: u16,
+    pub harassment_severity: u16,
+    pub scam_severity: u16,
+    /// 正面互动率估计方式，Wilson 模式下使用置信下界
+    pub scoring_mode: ScoringMode,
+    /// Wilson 区间的 z 值 × 1000
+    pub wilson_z_milli: u16,
+    /// 加权分中社交、学习、指导、安全分项的权重（基点，全为 0 时使用 base_score）
+    pub social_weight_bps: u16,
+    pub learning_weight_bps: u16,
+    pub mentoring_weight_bps: u16,
+    pub safety_weight_bps: u16,
+    /// 新账户观察期（秒）、期内分数上限、期内举报扣分倍数（基点）
+    pub probation_seconds: u32,
+    pub probation_score_cap: u16,
+    pub probation_report_multiplier_bps: u16,
+    /// 评分人加权分达到该值时全额计入（0 表示关闭），及评分人权重下限（基点）
+    pub rater_full_weight_score: u16,
+    pub rater_floor_bps: u16,
+}
+
+impl ScoringParams {
+    pub const LEN: usize = 4 + 1 + 1 + 2 + 2 + 2 + 1 + 2 + 2 + 2 + 2 * 4 + 4 + 2 + 2 + 2 + 2 + 2 * 5 + 4 + 2 + 2 + 2 * 4 + 1 + 2 + 2 * 4 + 4 + 2 + 2 + 2 + 2;
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.positive_threshold <= 100
+                && self.positive_rate_bonus_threshold <= 100
+                && self.learning_engagement_threshold <= 100
+                && self.decay_floor <= 1000
+                && self.min_voucher_score <= 1000
+                && self.pair_decay_bps <= 10_000
+                && self.wilson_z_milli <= 5_000
+                && self.probation_score_cap <= 1000
+                && self.probation_report_multiplier_bps >= 10_000
+                && self.rater_floor_bps <= 10_000,
+            ErrorCode::InvalidScoringParams
+        );
+        Ok(())
+    }
+}
+
+impl Default for ScoringParams {
+    fn default() -> Self {
+        engine::Params::default().into()
+    }
+}
+
+impl From<engine::Params> for ScoringParams {
+    fn from(params: engine::Params) -> Self {
+        Self {
+            match_weight: params.match_weight,
+            chat_weight: params.chat_weight,
+            helpful_response_weight: params.helpful_response_weight,
+            shared_content_weight: params.shared_content_weight,
+            positive_threshold: params.positive_threshold,
+            positive_rate_bonus_threshold: params.positive_rate_bonus_threshold,
+            positive_rate_bonus: params.positive_rate_bonus,
+            streak_bonus_days: params.streak_bonus_days,
+            streak_bonus_points: params.streak_bonus_points,
+            learning_engagement_threshold: params.learning_engagement_threshold,
+            learning_bonus: params.learning_bonus,
+            report_penalty: params.report_penalty,
+            validation_penalty: params.validation_penalty,
+            active_days: params.active_days,
+            active_multiplier_bps: params.active_multiplier_bps,
+            inactive_days: params.inactive_days,
+            inactive_multiplier_bps: params.inactive_multiplier_bps,
+            quality_min_interactions: params.quality_min_interactions,
+            quality_base_bps: params.quality_base_bps,
+            quality_range_bps: params.quality_range_bps,
+            decay_half_life_days: params.decay_half_life_days,
+            decay_floor: params.decay_floor,
+            vouch_weight_bps: params.vouch_weight_bps,
+            max_vouch_weight: params.max_vouch_weight,
+            min_voucher_score: params.min_voucher_score,
+            max_outstanding_vouches: params.max_outstanding_vouches,
+            voucher_penalty: params.voucher_penalty,
+            pair_window_seconds: params.pair_window_seconds,
+            pair_free_interactions: params.pair_free_interactions,
+            pair_decay_bps: params.pair_decay_bps,
+            no_show_severity: params.no_show_severity,
+            spam_severity: params.spam_severity,
+            harassment_severity: params.harassment_severity,
+            scam_severity: params.scam_severity,
+            scoring_mode: params.scoring_mode.into(),
+            wilson_z_milli: params.wilson_z_milli,
+            social_weight_bps: params.social_weight_bps,
+            learning_weight_bps: params.learning_weight_bps,
+            mentoring_weight_bps: params.mentoring_weight_bps,
+            safety_weight_bps: params.safety_weight_bps,
+            probation_seconds: params.probation_seconds,
+            probation_score_cap: params.probation_score_cap,
+            probation_report_multiplier_bps: params.probation_report_multiplier_bps,
+            rater_full_weight_score: params.rater_full_weight_score,
+            rater_floor_bps: params.rater_floor_bps,
+        }
+    }
+}
+
+impl From<ScoringParams> for engine::Params {
+    fn from(params: ScoringParams) -> Self {
+        Self {
+            match_weight: params.match_weight,
+            chat_weight: params.chat_weight,
+            helpful_response_weight: params.helpful_response_weight,
+            shared_content_weight: params.shared_content_weight,
+            positive_threshold: params.positive_threshold,
+            positive_rate_bonus_threshold: params.positive_rate_bonus_threshold,
+            positive_rate_bonus: params.positive_rate_bonus,
+            streak_bonus_days: params.streak_bonus_days,
+            streak_bonus_points: params.streak_bonus_points,
+            learning_engagement_threshold: params.learning_engagement_threshold,
+            learning_bonus: params.learning_bonus,
+            report_penalty: params.report_penalty,
+            validation_penalty: params.validation_penalty,
+            active_days: params.active_days,
+            active_multiplier_bps: params.active_multiplier_bps,
+            inactive_days: params.inactive_days,
+            inactive_multiplier_bps: params.inactive_multiplier_bps,
+            quality_min_interactions: params.quality_min_interactions,
+            quality_base_bps: params.quality_base_bps,
+            quality_range_bps: params.quality_range_bps,
+            decay_half_life_days: params.decay_half_life_days,
+            decay_floor: params.decay_floor,
+            vouch_weight_bps: params.vouch_weight_bps,
+            max_vouch_weight: params.max_vouch_weight,
+            min_voucher_score: params.min_voucher_score,
+            max_outstanding_vouches: params.max_outstanding_vouches,
+            voucher_penalty: params.voucher_penalty,
+            pair_window_seconds: params.pair_window_seconds,
+            pair_free_interactions: params.pair_free_interactions,
+            pair_decay_bps: params.pair_decay_bps,
+            no_show_severity: params.no_show_severity,
+            spam_severity: params.spam_severity,
+            harassment_severity: params.harassment_severity,
+            scam_severity: params.scam_severity,
+            scoring_mode: params.scoring_mode.into(),
+            wilson_z_milli: params.wilson_z_milli,
+            social_weight_bps: params.social_weight_bps,
+            learning_weight_bps: params.learning_weight_bps,
+            mentoring_weight_bps: params.mentoring_weight_bps,
+            safety_weight_bps: params.safety_weight_bps,
+            probation_seconds: params.probation_seconds,
+            probation_score_cap: params.probation_score_cap,
+            probation_report_multiplier_bps: params.probation_report_multiplier_bps,
+            rater_full_weight_score: params.rater_full_weight_score,
+            rater_floor_bps: params.rater_floor_bps,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScoringMode {
+    #[default]
+    Classic,
+    Wilson,
+}
+
+impl From<engine::ScoringMode> for ScoringMode {
+    fn from(mode: engine::ScoringMode) -> Self {
+        match mode {
+            engine::ScoringMode::Classic => ScoringMode::Classic,
+            engine::ScoringMode::Wilson => ScoringMode::Wilson,
+        }
+    }
+}
+
+impl From<ScoringMode> for engine::ScoringMode {
+    fn from(mode: ScoringMode) -> Self {
+        match mode {
+            ScoringMode::Classic => engine::ScoringMode::Classic,
+            ScoringMode::Wilson => engine::ScoringMode::Wilson,
+        }
+    }
+}
+
+/// 信誉证书：某一时刻加权分的快照，供第三方校验
+#[account]
+pub struct ScoreCertificate {
+    pub owner: Pubkey,
+    pub weighted_score: u16,
+    pub confidence_bps: u16,
+    pub issued_at: i64,
+    pub issued_slot: u64,
+    /// 该 slot 之后证书失效
+    pub expiry_slot: u64,
+    /// 签发时的评分参数版本
+    pub config_version: u32,
+    pub bump: u8,
+}
+
+impl ScoreCertificate {
+    pub const LEN: usize = 8 + 32 + 2 + 2 + 8 + 8 + 8 + 4 + 1;
+    /// 最长有效期（约 7 天，按 400ms / slot 计）
+    pub const MAX_VALIDITY_SLOTS: u64 = 1_512_000;
+}
+
+/// 纪元结算根：链下计算的 (owner, score, epoch) Merkle 根
+#[account]
+pub struct EpochRoot {
+    pub epoch: u64,
+    pub root: [u8; 32],
+    pub leaf_count: u32,
+    pub publisher: Pubkey,
+    pub published_at: i64,
+    /// 链下计算所用的评分参数版本
+    pub config_version: u32,
+    pub bump: u8,
+    /// 链下读取分数的时间，之后有变动的账户不能结算
+    pub snapshot_at: i64,
+}
+
+impl EpochRoot {
+    pub const LEN: usize = 8 + 8 + 32 + 4 + 32 + 8 + 4 + 1 + 8;
+}
+
+/// 纪元结算状态（单例）
+#[account]
+pub struct EpochState {
+    /// 最新发布的纪元，只有该纪元可以结算
+    pub latest_epoch: u64,
+    pub bump: u8,
+}
+
+impl EpochState {
+    pub const LEN: usize = 8 + 8 + 1;
+}
+
+/// `get_weighted_score_detailed` 的返回值
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct WeightedScore {
+    pub score: u16,
+    /// 置信度（基点），样本越少越低
+    pub confidence_bps: u16,
+}
+
+impl From<engine::ScoreEstimate> for WeightedScore {
+    fn from(estimate: engine::ScoreEstimate) -> Self {
+        Self {
+            score: estimate.score,
+            confidence_bps: estimate.confidence_bps,
+        }
+    }
+}
+
+/// 举报配置
+#[account]
+pub struct ReportConfig {
+    pub admin: Pubkey,
+    /// 每次举报需托管的保证金（0 表示关闭）
+    pub bond_lamports: u64,
+    /// 被驳回举报的保证金去向
+    pub treasury: Pubkey,
+    pub bump: u8,
+    /// 同一用户两次申诉之间的最短间隔（秒）
+    pub appeal_cooldown_seconds: i64,
+}
+
+impl ReportConfig {
+    pub const LEN: usize = 8 + 32 + 8 + 32 + 1 + 8;
+}
+
+/// 举报保证金托管账户
+#[account]
+pub struct BondVault {
+    pub bump: u8,
+}
+
+impl BondVault {
+    pub const LEN: usize = 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportStatus {
+    Pending,    // 待审核
+    Validated,  // 审核通过
+    Dismissed,  // 已驳回
+    Overturned, // 申诉后推翻
+    Appealed,   // 申诉中
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum InteractionType {
+    Match,
+    Chat,
+    HelpfulResponse,
+    SharedContent,
+    // 负面行为（仅能通过 record_negative_interaction 由证明方记录）
+    NoShow,
+    Spam,
+    Harassment,
+    Scam,
+}
+
+impl InteractionType {
+    /// 是否要求双方在 social-graph 中存在有效匹配
+    pub fn requires_match(&self) -> bool {
+        matches!(self, InteractionType::Match | InteractionType::Chat)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        engine::InteractionKind::from(*self).is_negative()
+    }
+}
+
+/// 批量记录中的一条互动
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct InteractionEntry {
+    pub owner: Pubkey,
+    pub interaction_type: InteractionType,
+    pub quality_score: u8,
+    /// 互动对方（仅 Match / Chat，且必须指定）
+    pub counterparty: Option<Pubkey>,
+}
+
+impl InteractionEntry {
+    /// 单笔交易的条目上限（受计算单元与账户数量限制）
+    pub const MAX_BATCH: usize = 8;
+}
+
+/// 批量记录中单条互动的结果
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct InteractionBatchResult {
+    pub owner: Pubkey,
+    pub interaction_type: InteractionType,
+    pub old_score: u16,
+    pub new_score: u16,
+    pub counterparty: Option<Pubkey>,
+}
+
+impl From<InteractionType> for engine::InteractionKind {
+    fn from(interaction_type: InteractionType) -> Self {
+        match interaction_type {
+            InteractionType::Match => engine::InteractionKind::Match,
+            InteractionType::Chat => engine::InteractionKind::Chat,
+            InteractionType::HelpfulResponse => engine::InteractionKind::HelpfulResponse,
+            InteractionType::SharedContent => engine::InteractionKind::SharedContent,
+            InteractionType::NoShow => engine::InteractionKind::NoShow,
+            InteractionType::Spam => engine::InteractionKind::Spam,
+            InteractionType::Harassment => engine::InteractionKind::Harassment,
+            InteractionType::Scam => engine::InteractionKind::Scam,
+        }
+    }
+}
+
+// ==========================================
+// CPI 守卫 / CPI Guard
+// ==========================================
+
+/// 供其他程序按信誉分设置门槛（启用 `cpi` feature 后可用）
+#[cfg(feature = "cpi")]
+pub mod guard {
+    use super::*;
+
+    /// 读取并校验 TrustScore（owner 程序与 PDA，不符时返回 `InvalidTrustScoreAccount`）
+    /// 及评分参数（不符时返回 `InvalidScoringConfigAccount`），
+    /// 账户非正常状态时返回 `AccountSuspended`，加权分低于 `min_score` 时返回 `ScoreBelowMinimum`，否则返回加权分
+    pub fn require_min_trust(
+        trust_score: &AccountInfo,
+        scoring_config: &AccountInfo,
+        user: &Pubkey,
+        min_score: u16,
+    ) -> Result<u16> {
+        require_keys_eq!(*trust_score.owner, crate::ID, ErrorCode::InvalidTrustScoreAccount);
+        require_keys_eq!(*scoring_config.owner, crate::ID, ErrorCode::InvalidScoringConfigAccount);
+
+        let score = TrustScore::try_deserialize(&mut &trust_score.try_borrow_data()?[..])?;
+        require_keys_eq!(score.owner, *user, ErrorCode::InvalidTrustScoreAccount);
+        let expected = Pubkey::create_program_address(
+            &[b"trust_score", user.as_ref(), &[score.bump]],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::InvalidTrustScoreAccount)?;
+        require_keys_eq!(expected, trust_score.key(), ErrorCode::InvalidTrustScoreAccount);
+
+        let config = ScoringConfig::try_deserialize(&mut &scoring_config.try_borrow_data()?[..])?;
+        let expected = Pubkey::create_program_address(&[b"scoring_config", &[config.bump]], &crate::ID)
+            .map_err(|_| ErrorCode::InvalidScoringConfigAccount)?;
+        require_keys_eq!(expected, scoring_config.key(), ErrorCode::InvalidScoringConfigAccount);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(score.is_active(now), ErrorCode::AccountSuspended);
+        let weighted = engine::weighted_score(&score.stats(), &config.params.into(), now);
+        require!(weighted >= min_score, ErrorCode::ScoreBelowMinimum);
+
+        Ok(weighted)
+    }
+
+    /// 账户约束用：调用方已通过 `seeds::program = trust_score::ID` 校验 PDA
+    ///
+    /// ```ignore
+    /// #[account(
+    ///     seeds = [b"trust_score", user.key().as_ref()],
+    ///     bump = user_trust_score.bump,
+    ///     seeds::program = trust_score::ID,
+    ///     constraint = trust_score::guard::meets_min_trust(&user_trust_score, &scoring_config, 300)
+    ///         @ ErrorCode::ScoreBelowMinimum,
+    /// )]
+    /// ```
+    pub fn meets_min_trust(trust_score: &TrustScore, scoring_config: &ScoringConfig, min_score: u16) -> bool {
+        let Ok(clock) = Clock::get() else {
+            return false;
+        };
+        trust_score.is_active(clock.unix_timestamp)
+            && engine::weighted_score(
+                &trust_score.stats(),
+                &scoring_config.params.into(),
+                clock.unix_timestamp,
+            ) >= min_score
+    }
+}
+
+// ==========================================
+// 外部程序账户 / External Accounts
+// ==========================================
+
+/// social-graph 程序 ID（与 programs/social-graph 的 declare_id 保持一致）
+pub const SOCIAL_GRAPH_PROGRAM_ID: Pubkey = pubkey!("EmKvmnfXrcgmcj4yT2K12rM2dMTZS2MMAfUB8769veHK");
+
+/// social_graph::MatchEdge 的只读镜像
+/// social-graph 依赖本程序的 crate 读取 TrustScore，这里不反向依赖以免循环依赖
+#[derive(AnchorDeserialize)]
+pub struct MatchEdgeView {
+    pub user_a: Pubkey,
+    pub user_b: Pubkey,
+    pub created_at: i64,
+    pub is_active: bool,
+    pub interaction_count: u32,
+    pub deactivated_at: Option<i64>,
+    pub bump: u8,
+    pub initiator: Pubkey,
+    pub accepted_at: Option<i64>,
+}
+
+impl MatchEdgeView {
+    /// sha256("account:MatchEdge")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [143, 165, 95, 213, 150, 104, 80, 104];
+
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() >= 8 && data[..8] == Self::DISCRIMINATOR,
+            ErrorCode::InvalidMatchEdge
+        );
+        Self::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::InvalidMatchEdge))
+    }
+}
+
+/// trust-score-mini 程序 ID（devnet 上的早期精简版）
+pub const TRUST_SCORE_MINI_PROGRAM_ID: Pubkey = pubkey!("3FWDkwEPfVVZmxXS4f3pDaJpg4qf7GL5ir89DtXSwAjR");
+
+/// trust_score_mini::TrustAccount 的只读镜像
+/// mini 使用 anchor 0.27，这里不依赖其 crate
+#[derive(AnchorDeserialize)]
+pub struct MiniTrustAccountView {
+    pub owner: Pubkey,
+    pub score: u64,
+    pub total_interactions: u64,
+}
+
+impl MiniTrustAccountView {
+    /// sha256("account:TrustAccount")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [53, 140, 220, 2, 123, 7, 49, 109];
+    /// sha256("global:close")[..8]，mini 的 close 指令
+    pub const CLOSE_DISCRIMINATOR: [u8; 8] = [98, 165, 201, 177, 108, 65, 206, 96];
+
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() >= 8 && data[..8] == Self::DISCRIMINATOR,
+            ErrorCode::InvalidMiniAccount
+        );
+        Self::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::InvalidMiniAccount))
+    }
+}
+
+// ==========================================
+// 上下文
+// ==========================================
+
+#[derive(Accounts)]
+pub struct InitializeTrustScore<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = TrustScore::LEN,
+        seeds = [b"trust_score", owner.key().as_ref()],
+        bump
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ScoreHistory::LEN,
+        seeds = [b"score_history", owner.key().as_ref()],
+        bump
+    )]
+    pub score_history: Box<Account<'info, ScoreHistory>>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Box<Account<'info, ScoringConfig>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordInteraction<'info> {
+    #[account(
+        mut,
+        seeds = [b"trust_score", owner.key().as_ref()],
+        bump = trust_score.bump,
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+
+    /// CHECK: 互动对方
+    pub counterparty: UncheckedAccount<'info>,
+
+    /// CHECK: social-graph 的 MatchEdge，在 verify_match_edge 中校验归属与 PDA
+    pub match_edge: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = InteractionPair::LEN,
+        seeds = [
+            b"interaction_pair",
+            owner.key().as_ref(),
+            counterparty.key().as_ref(),
+        ],
+        bump
+    )]
+    pub interaction_pair: Box<Account<'info, InteractionPair>>,
+
+    /// CHECK: 评分人（即对方）的信誉账户 PDA，可能尚未创建，有证明时在 rater_weight_bps 中反序列化
+    #[account(
+        seeds = [b"trust_score", counterparty.key().as_ref()],
+        bump,
+    )]
+    pub rater_trust_score: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"score_history", owner.key().as_ref()],
+        bump = score_history.bump,
+    )]
+    pub score_history: Box<Account<'info, ScoreHistory>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(
+        seeds = [b"authority_registry", AuthorityRole::Attester.seed()],
+        bump = attester_registry.bump,
+    )]
+    pub attester_registry: Account<'info, AuthorityRegistry>,
+
+    /// 证明方共同签名（与 ed25519 载荷二选一）
+    pub attester: Option<Signer<'info>>,
+
+    /// CHECK: 指令 sysvar，用于读取 ed25519 验签指令
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordNegativeInteraction<'info> {
+    #[account(
+        mut,
+        seeds = [b"trust_score", user.key().as_ref()],
+        bump = trust_score.bump,
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        mut,
+        seeds = [b"score_history", user.key().as_ref()],
+        bump = score_history.bump,
+    )]
+    pub score_history: Box<Account<'info, ScoreHistory>>,
+
+    /// CHECK: 被记录的用户
+    pub user: AccountInfo<'info>,
+
+    /// CHECK: 受影响的一方（可选，计入证明载荷）
+    pub counterparty: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(
+        seeds = [b"authority_registry", AuthorityRole::Attester.seed()],
+        bump = attester_registry.bump,
+    )]
+    pub attester_registry: Account<'info, AuthorityRegistry>,
+
+    /// 证明方共同签名（与 ed25519 载荷二选一）
+    pub attester: Option<Signer<'info>>,
+
+    /// CHECK: 指令 sysvar，用于读取 ed25519 验签指令
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct RecordLearning<'info> {
+    #[account(
+        mut,
+        seeds = [b"trust_score", owner.key().as_ref()],
+        bump = trust_score.bump,
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        mut,
+        seeds = [b"score_history", owner.key().as_ref()],
+        bump = score_history.bump,
+    )]
+    pub score_history: Box<Account<'info, ScoreHistory>>,
+
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(
+        seeds = [b"authority_registry", AuthorityRole::Attester.seed()],
+        bump = attester_registry.bump,
+    )]
+    pub attester_registry: Account<'info, AuthorityRegistry>,
+
+    /// 证明方共同签名（与 ed25519 载荷二选一）
+    pub attester: Option<Signer<'info>>,
+
+    /// CHECK: 指令 sysvar，用于读取 ed25519 验签指令
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct ReportUser<'info> {
+    #[account(
+        init,
+        payer = reporter,
+        space = Report::LEN,
+        seeds = [b"report", reporter.key().as_ref(), reported_user.key().as_ref()],
+        bump
+    )]
+    pub report: Account<'info, Report>,
+
+    #[account(
+        mut,
+        seeds = [b"trust_score", reported_user.key().as_ref()],
+        bump = reported_trust_score.bump,
+    )]
+    pub reported_trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        mut,
+        seeds = [b"score_history", reported_user.key().as_ref()],
+        bump = reported_score_history.bump,
+    )]
+    pub reported_score_history: Box<Account<'info, ScoreHistory>>,
+    
+    /// CHECK: 被举报的用户
+    pub reported_user: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+
+    #[account(
+        seeds = [b"trust_score", reporter.key().as_ref()],
+        bump = reporter_trust_score.bump,
+    )]
+    pub reporter_trust_score: Box<Account<'info, TrustScore>>,
+
+    #[account(
+        seeds = [b"report_config"],
+        bump = report_config.bump,
+    )]
+    pub report_config: Account<'info, ReportConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault"],
+        bump = bond_vault.bump,
+    )]
+    pub bond_vault: Account<'info, BondVault>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Box<Account<'info, ScoringConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveReport<'info> {
+    #[account(
+        mut,
+        seeds = [b"report", report.reporter.as_ref(), report.reported_user.as_ref()],
+        bump = report.bump,
+    )]
+    pub report: Account<'info, Report>,
+
+    #[account(
+        mut,
+        seeds = [b"trust_score", report.reported_user.as_ref()],
+        bump = reported_trust_score.bump,
+    )]
+    pub reported_trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        mut,
+        seeds = [b"score_history", report.reported_user.as_ref()],
+        bump = reported_score_history.bump,
+    )]
+    pub reported_score_history: Box<Account<'info, ScoreHistory>>,
+
+    #[account(
+        seeds = [b"authority_registry", AuthorityRole::Moderator.seed()],
+        bump = moderator_registry.bump,
+        constraint = moderator_registry.authorities.contains(moderator.key) @ ErrorCode::UnauthorizedModerator,
+    )]
+    pub moderator_registry: Account<'info, AuthorityRegistry>,
+
+    pub moderator: Signer<'info>,
+
+    #[account(
+        seeds = [b"report_config"],
+        bump = report_config.bump,
+    )]
+    pub report_config: Account<'info, ReportConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault"],
+        bump = bond_vault.bump,
+    )]
+    pub bond_vault: Account<'info, BondVault>,
+
+    /// CHECK: 金库，举报被驳回时接收罚没的保证金
+    #[account(mut, address = report_config.treasury)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeReportConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = ReportConfig::LEN,
+        seeds = [b"report_config"],
+        bump
+    )]
+    pub report_config: Account<'info, ReportConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = BondVault::LEN,
+        seeds = [b"bond_vault"],
+        bump
+    )]
+    pub bond_vault: Account<'info, BondVault>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// 举报配置管理员只能是程序升级权限，防止部署后被抢先初始化并指定金库
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::TrustScore>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateReportConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"report_config"],
+        bump = report_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub report_config: Account<'info, ReportConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FileAppeal<'info> {
+    #[account(
+        init,
+        payer = appellant,
+        space = Appeal::LEN,
+        seeds = [b"appeal", report.key().as_ref()],
+        bump
+    )]
+    pub appeal: Account<'info, Appeal>,
+
+    #[account(
+        mut,
+        seeds = [b"report", report.reporter.as_ref(), report.reported_user.as_ref()],
+        bump = report.bump,
+        constraint = report.reported_user == appellant.key() @ ErrorCode::Unauthorized,
+    )]
+    pub report: Account<'info, Report>,
+
+    #[account(
+        mut,
+        seeds = [b"trust_score", appellant.key().as_ref()],
+        bump = trust_score.bump,
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        seeds = [b"report_config"],
+        bump = report_config.bump,
+    )]
+    pub report_config: Account<'info, ReportConfig>,
+
+    #[account(mut)]
+    pub appellant: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveAppeal<'info> {
+    #[account(
+        mut,
+        seeds = [b"appeal", report.key().as_ref()],
+        bump = appeal.bump,
+    )]
+    pub appeal: Account<'info, Appeal>,
+
+    #[account(
+        mut,
+        seeds = [b"report", report.reporter.as_ref(), report.reported_user.as_ref()],
+        bump = report.bump,
+    )]
+    pub report: Account<'info, Report>,
+
+    #[account(
+        mut,
+        seeds = [b"trust_score", report.reported_user.as_ref()],
+        bump = reported_trust_score.bump,
+    )]
+    pub reported_trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        mut,
+        seeds = [b"score_history", report.reported_user.as_ref()],
+        bump = reported_score_history.bump,
+    )]
+    pub reported_score_history: Box<Account<'info, ScoreHistory>>,
+
+    #[account(
+        seeds = [b"authority_registry", AuthorityRole::Reviewer.seed()],
+        bump = reviewer_registry.bump,
+        constraint = reviewer_registry.authorities.contains(reviewer.key) @ ErrorCode::UnauthorizedReviewer,
+    )]
+    pub reviewer_registry: Account<'info, AuthorityRegistry>,
+
+    pub reviewer: Signer<'info>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(
+        seeds = [b"report_config"],
+        bump = report_config.bump,
+    )]
+    pub report_config: Account<'info, ReportConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault"],
+        bump = bond_vault.bump,
+    )]
+    pub bond_vault: Account<'info, BondVault>,
+
+    /// CHECK: 金库，申诉成立时接收罚没的保证金
+    #[account(mut, address = report_config.treasury)]
+    pub treasury: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawReportBond<'info> {
+    #[account(
+        mut,
+        seeds = [b"report", reporter.key().as_ref(), report.reported_user.as_ref()],
+        bump = report.bump,
+    )]
+    pub report: Account<'info, Report>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault"],
+        bump = bond_vault.bump,
+    )]
+    pub bond_vault: Account<'info, BondVault>,
+
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAccountStatus<'info> {
+    #[account(
+        mut,
+        seeds = [b"trust_score", trust_score.owner.as_ref()],
+        bump = trust_score.bump,
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        seeds = [b"authority_registry", AuthorityRole::Moderator.seed()],
+        bump = moderator_registry.bump,
+        constraint = moderator_registry.authorities.contains(moderator.key) @ ErrorCode::UnauthorizedModerator,
+    )]
+    pub moderator_registry: Account<'info, AuthorityRegistry>,
+
+    pub moderator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetStatus<'info> {
+    #[account(
+        seeds = [b"trust_score", user.key().as_ref()],
+        bump = trust_score.bump,
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+
+    /// CHECK: 查询的用户
+    pub user: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetScore<'info> {
+    #[account(
+        seeds = [b"trust_score", user.key().as_ref()],
+        bump = trust_score.bump,
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+    
+    /// CHECK: 查询的用户
+    pub user: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+}
+
+#[derive(Accounts)]
+pub struct IssueScoreCertificate<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ScoreCertificate::LEN,
+        seeds = [b"score_certificate", owner.key().as_ref()],
+        bump
+    )]
+    pub certificate: Account<'info, ScoreCertificate>,
+
+    #[account(
+        seeds = [b"trust_score", owner.key().as_ref()],
+        bump = trust_score.bump,
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyCertificate<'info> {
+    #[account(
+        seeds = [b"score_certificate", user.key().as_ref()],
+        bump = certificate.bump,
+    )]
+    pub certificate: Account<'info, ScoreCertificate>,
+
+    /// CHECK: 证书所属用户
+    pub user: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"trust_score", user.key().as_ref()],
+        bump = trust_score.bump,
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyDecay<'info> {
+    #[account(
+        mut,
+        seeds = [b"trust_score", trust_score.owner.as_ref()],
+        bump = trust_score.bump,
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        mut,
+        seeds = [b"score_history", trust_score.owner.as_ref()],
+        bump = score_history.bump,
+    )]
+    pub score_history: Box<Account<'info, ScoreHistory>>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyDecayBatch<'info> {
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+}
+
+#[derive(Accounts)]
+pub struct RecordInteractionsBatch<'info> {
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(
+        seeds = [b"authority_registry", AuthorityRole::Attester.seed()],
+        bump = attester_registry.bump,
+        constraint = attester_registry.authorities.contains(attester.key) @ ErrorCode::UnauthorizedAttester,
+    )]
+    pub attester_registry: Account<'info, AuthorityRegistry>,
+
+    /// 证明方，同时为新建的 InteractionPair 支付租金
+    #[account(mut)]
+    pub attester: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct PublishEpochRoot<'info> {
+    #[account(
+        init,
+        payer = attester,
+        space = EpochRoot::LEN,
+        seeds = [b"epoch_root", epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub epoch_root: Account<'info, EpochRoot>,
+
+    #[account(
+        init_if_needed,
+        payer = attester,
+        space = EpochState::LEN,
+        seeds = [b"epoch_state"],
+        bump
+    )]
+    pub epoch_state: Account<'info, EpochState>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(
+        seeds = [b"authority_registry", AuthorityRole::Attester.seed()],
+        bump = attester_registry.bump,
+        constraint = attester_registry.authorities.contains(attester.key) @ ErrorCode::UnauthorizedAttester,
+    )]
+    pub attester_registry: Account<'info, AuthorityRegistry>,
+
+    #[account(mut)]
+    pub attester: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimEpochScore<'info> {
+    #[account(
+        seeds = [b"epoch_root", epoch_root.epoch.to_le_bytes().as_ref()],
+        bump = epoch_root.bump,
+    )]
+    pub epoch_root: Account<'info, EpochRoot>,
+
+    #[account(
+        seeds = [b"epoch_state"],
+        bump = epoch_state.bump,
+    )]
+    pub epoch_state: Account<'info, EpochState>,
+
+    #[account(
+        mut,
+        seeds = [b"trust_score", trust_score.owner.as_ref()],
+        bump = trust_score.bump,
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        mut,
+        seeds = [b"score_history", trust_score.owner.as_ref()],
+        bump = score_history.bump,
+    )]
+    pub score_history: Box<Account<'info, ScoreHistory>>,
+
+    /// 提交结算的账户（任何人）
+    pub claimer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpgradeTrustScoreLayout<'info> {
+    /// CHECK: 旧布局无法按当前结构反序列化，指令内校验 discriminator 与 PDA
+    #[account(mut, owner = crate::ID)]
+    pub trust_score: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpgradeScoringConfig<'info> {
+    /// CHECK: 旧布局无法按当前结构反序列化，指令内校验 discriminator 与 admin
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"scoring_config"],
+        bump,
+    )]
+    pub scoring_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeScoreHistory<'info> {
+    #[account(
+        seeds = [b"trust_score", trust_score.owner.as_ref()],
+        bump = trust_score.bump,
+    )]
+    pub trust_score: Account<'info, TrustScore>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ScoreHistory::LEN,
+        seeds = [b"score_history", trust_score.owner.as_ref()],
+        bump
+    )]
+    pub score_history: Box<Account<'info, ScoreHistory>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VouchFor<'info> {
+    #[account(
+        init,
+        payer = voucher,
+        space = Vouch::LEN,
+        seeds = [b"vouch", voucher.key().as_ref(), vouchee.key().as_ref()],
+        bump
+    )]
+    pub vouch: Account<'info, Vouch>,
+
+    #[account(
+        mut,
+        seeds = [b"trust_score", voucher.key().as_ref()],
+        bump = voucher_trust_score.bump,
+    )]
+    pub voucher_trust_score: Box<Account<'info, TrustScore>>,
+
+    #[account(
+        mut,
+        seeds = [b"trust_score", vouchee.key().as_ref()],
+        bump = vouchee_trust_score.bump,
+    )]
+    pub vouchee_trust_score: Box<Account<'info, TrustScore>>,
+
+    #[account(
+        mut,
+        seeds = [b"score_history", vouchee.key().as_ref()],
+        bump = vouchee_score_history.bump,
+    )]
+    pub vouchee_score_history: Box<Account<'info, ScoreHistory>>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Box<Account<'info, ScoringConfig>>,
+
+    #[account(mut)]
+    pub voucher: Signer<'info>,
+
+    /// CHECK: 被担保的用户
+    pub vouchee: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVouch<'info> {
+    #[account(
+        mut,
+        close = voucher,
+        seeds = [b"vouch", voucher.key().as_ref(), vouch.vouchee.as_ref()],
+        bump = vouch.bump,
+    )]
+    pub vouch: Account<'info, Vouch>,
+
+    #[account(
+        mut,
+        seeds = [b"trust_score", voucher.key().as_ref()],
+        bump = voucher_trust_score.bump,
+    )]
+    pub voucher_trust_score: Box<Account<'info, TrustScore>>,
+
+    #[account(
+        mut,
+        seeds = [b"trust_score", vouch.vouchee.as_ref()],
+        bump = vouchee_trust_score.bump,
+    )]
+    pub vouchee_trust_score: Box<Account<'info, TrustScore>>,
+
+    #[account(
+        mut,
+        seeds = [b"score_history", vouch.vouchee.as_ref()],
+        bump = vouchee_score_history.bump,
+    )]
+    pub vouchee_score_history: Box<Account<'info, ScoreHistory>>,
+
+    #[account(mut)]
+    pub voucher: Signer<'info>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Box<Account<'info, ScoringConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateTrustScore<'info> {
+    #[account(
+        mut,
+        close = old_owner,
+        seeds = [b"trust_score", old_owner.key().as_ref()],
+        bump = old_trust_score.bump,
+    )]
+    pub old_trust_score: Box<Account<'info, TrustScore>>,
+
+    #[account(
+        mut,
+        close = old_owner,
+        seeds = [b"score_history", old_owner.key().as_ref()],
+        bump = old_score_history.bump,
+    )]
+    pub old_score_history: Box<Account<'info, ScoreHistory>>,
+
+    #[account(
+        init,
+        payer = new_owner,
+        space = TrustScore::LEN,
+        seeds = [b"trust_score", new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_trust_score: Box<Account<'info, TrustScore>>,
+
+    #[account(
+        init,
+        payer = new_owner,
+        space = ScoreHistory::LEN,
+        seeds = [b"score_history", new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_score_history: Box<Account<'info, ScoreHistory>>,
+
+    #[account(mut)]
+    pub old_owner: Signer<'info>,
+
+    #[account(mut)]
+    pub new_owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Box<Account<'info, ScoringConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateFromMini<'info> {
+    /// CHECK: mini 程序的 TrustAccount，指令内校验 discriminator 与 owner
+    #[account(
+        mut,
+        owner = TRUST_SCORE_MINI_PROGRAM_ID,
+        seeds = [b"trust_score", user.key().as_ref()],
+        bump,
+        seeds::program = TRUST_SCORE_MINI_PROGRAM_ID,
+    )]
+    pub mini_trust_account: UncheckedAccount<'info>,
+
+    /// 已有账户时合并，否则新建
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = TrustScore::LEN,
+        seeds = [b"trust_score", user.key().as_ref()],
+        bump
+    )]
+    pub trust_score: Box<Account<'info, TrustScore>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ScoreHistory::LEN,
+        seeds = [b"score_history", user.key().as_ref()],
+        bump
+    )]
+    pub score_history: Box<Account<'info, ScoreHistory>>,
+
+    /// CHECK: 被迁移的用户，接收 mini 账户退还的租金
+    #[account(mut)]
+    pub user: UncheckedAccount<'info>,
+
+    /// CHECK: 本程序的迁移签名 PDA，mini 的 close 只接受它的签名
+    #[account(seeds = [b"mini_migrator"], bump)]
+    pub mini_migrator: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Box<Account<'info, ScoringConfig>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: trust-score-mini 程序
+    #[account(address = TRUST_SCORE_MINI_PROGRAM_ID)]
+    pub mini_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureGuardians<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = GuardianSet::LEN,
+        seeds = [b"guardian_set", owner.key().as_ref()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitiateRecovery<'info> {
+    #[account(
+        seeds = [b"guardian_set", guardian_set.owner.as_ref()],
+        bump = guardian_set.bump,
+        constraint = guardian_set.guardians.contains(guardian.key) @ ErrorCode::UnauthorizedGuardian,
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        init,
+        payer = guardian,
+        space = RecoveryRequest::LEN,
+        seeds = [b"recovery", guardian_set.owner.as_ref()],
+        bump
+    )]
+    pub recovery_request: Account<'info, RecoveryRequest>,
+
+    #[account(mut)]
+    pub guardian: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveRecovery<'info> {
+    #[account(
+        seeds = [b"guardian_set", recovery_request.old_owner.as_ref()],
+        bump = guardian_set.bump,
+        constraint = guardian_set.guardians.contains(guardian.key) @ ErrorCode::UnauthorizedGuardian,
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        mut,
+        seeds = [b"recovery", recovery_request.old_owner.as_ref()],
+        bump = recovery_request.bump,
+    )]
+    pub recovery_request: Account<'info, RecoveryRequest>,
+
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRecovery<'info> {
+    #[account(
+        mut,
+        close = initiator,
+        seeds = [b"recovery", old_owner.key().as_ref()],
+        bump = recovery_request.bump,
+    )]
+    pub recovery_request: Account<'info, RecoveryRequest>,
+
+    pub old_owner: Signer<'info>,
+
+    /// CHECK: 发起恢复的监护人，接收退还的租金
+    #[account(mut, address = recovery_request.initiator)]
+    pub initiator: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VetoRecovery<'info> {
+    #[account(
+        seeds = [b"guardian_set", recovery_request.old_owner.as_ref()],
+        bump = guardian_set.bump,
+        constraint = guardian_set.guardians.contains(guardian.key) @ ErrorCode::UnauthorizedGuardian,
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        mut,
+        seeds = [b"recovery", recovery_request.old_owner.as_ref()],
+        bump = recovery_request.bump,
+        has_one = initiator,
+    )]
+    pub recovery_request: Account<'info, RecoveryRequest>,
+
+    pub guardian: Signer<'info>,
+
+    /// CHECK: 发起恢复的监护人，请求被否决关闭时接收退还的租金
+    #[account(mut)]
+    pub initiator: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRecovery<'info> {
+    #[account(
+        seeds = [b"guardian_set", recovery_request.old_owner.as_ref()],
+        bump = guardian_set.bump,
+    )]
+    pub guardian_set: Box<Account<'info, GuardianSet>>,
+
+    #[account(
+        mut,
+        close = initiator,
+        seeds = [b"recovery", recovery_request.old_owner.as_ref()],
+        bump = recovery_request.bump,
+        has_one = new_owner @ ErrorCode::InvalidRecoveryTarget,
+        has_one = initiator,
+    )]
+    pub recovery_request: Box<Account<'info, RecoveryRequest>>,
+
+    #[account(
+        mut,
+        close = new_owner,
+        seeds = [b"trust_score", recovery_request.old_owner.as_ref()],
+        bump = old_trust_score.bump,
+    )]
+    pub old_trust_score: Box<Account<'info, TrustScore>>,
+
+    #[account(
+        mut,
+        close = new_owner,
+        seeds = [b"score_history", recovery_request.old_owner.as_ref()],
+        bump = old_score_history.bump,
+    )]
+    pub old_score_history: Box<Account<'info, ScoreHistory>>,
+
+    #[account(
+        init,
+        payer = new_owner,
+        space = TrustScore::LEN,
+        seeds = [b"trust_score", new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_trust_score: Box<Account<'info, TrustScore>>,
+
+    #[account(
+        init,
+        payer = new_owner,
+        space = ScoreHistory::LEN,
+        seeds = [b"score_history", new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_score_history: Box<Account<'info, ScoreHistory>>,
+
+    #[account(mut)]
+    pub new_owner: Signer<'info>,
+
+    /// CHECK: 发起恢复的监护人，接收退还的租金
+    #[account(mut)]
+    pub initiator: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+    )]
+    pub scoring_config: Box<Account<'info, ScoringConfig>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeScoringConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = ScoringConfig::LEN,
+        seeds = [b"scoring_config"],
+        bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// 评分参数管理员只能是程序升级权限，防止部署后被抢先初始化
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::TrustScore>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateScoringConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"scoring_config"],
+        bump = scoring_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(role: AuthorityRole)]
+pub struct InitializeAuthorityRegistry<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = AuthorityRegistry::LEN,
+        seeds = [b"authority_registry", role.seed()],
+        bump
+    )]
+    pub authority_registry: Account<'info, AuthorityRegistry>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// 注册表管理员只能是程序升级权限，防止部署后被抢先初始化
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::TrustScore>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAuthorityRegistry<'info> {
+    #[account(
+        mut,
+        seeds = [b"authority_registry", authority_registry.role.seed()],
+        bump = authority_registry.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub authority_registry: Account<'info, AuthorityRegistry>,
+
+    pub admin: Signer<'info>,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Invalid quality score. Must be between 0-100.")]
+    InvalidQualityScore,
+    
+    #[msg("Reason too long. Max 200 characters.")]
+    ReasonTooLong,
+
+    #[msg("Unauthorized. Only the registry admin can perform this action.")]
+    Unauthorized,
+
+    #[msg("Authority is already registered.")]
+    AuthorityAlreadyRegistered,
+
+    #[msg("Authority registry is full.")]
+    AuthorityRegistryFull,
+
+    #[msg("Authority not found in registry.")]
+    AuthorityNotFound,
+
+    #[msg("Attestation required. Provide an attester co-signature or an ed25519 signed payload.")]
+    AttestationRequired,
+
+    #[msg("Attester is not registered.")]
+    UnauthorizedAttester,
+
+    #[msg("Invalid attestation payload.")]
+    InvalidAttestation,
+
+    #[msg("Cannot report yourself.")]
+    SelfReport,
+
+    #[msg("Report has already been resolved.")]
+    ReportAlreadyResolved,
+
+    #[msg("Moderator is not registered.")]
+    UnauthorizedModerator,
+
+    #[msg("Bond vault has insufficient lamports.")]
+    InsufficientBondVault,
+
+    #[msg("Invalid scoring parameters. Thresholds must be between 0-100, scores within 0-1000 and basis points at most 10000.")]
+    InvalidScoringParams,
+
+    #[msg("Account must be writable.")]
+    AccountNotWritable,
+
+    #[msg("Account is not a valid TrustScore PDA.")]
+    InvalidTrustScoreAccount,
+
+    #[msg("Account is not a valid ScoreHistory PDA.")]
+    InvalidScoreHistoryAccount,
+
+    #[msg("Invalid remaining accounts layout.")]
+    InvalidRemainingAccounts,
+
+    #[msg("Invalid guardian config. Need 1-5 unique guardians, a valid threshold and a timelock of at least 1 day.")]
+    InvalidGuardianConfig,
+
+    #[msg("Signer is not a guardian of this account.")]
+    UnauthorizedGuardian,
+
+    #[msg("Invalid recovery target.")]
+    InvalidRecoveryTarget,
+
+    #[msg("Guardian has already approved this recovery.")]
+    RecoveryAlreadyApproved,
+
+    #[msg("Not enough guardian approvals to execute recovery.")]
+    RecoveryThresholdNotMet,
+
+    #[msg("Recovery timelock has not expired yet.")]
+    RecoveryTimelockActive,
+
+    #[msg("Cannot vouch for yourself.")]
+    SelfVouch,
+
+    #[msg("Voucher trust score is below the minimum required to vouch.")]
+    VoucherScoreTooLow,
+
+    #[msg("Voucher has reached the maximum number of outstanding vouches.")]
+    TooManyVouches,
+
+    #[msg("Account is not a valid Vouch for the reported user.")]
+    InvalidVouchAccount,
+
+    #[msg("Match and Chat interactions require a counterparty and their MatchEdge.")]
+    MatchEdgeRequired,
+
+    #[msg("Account is not a valid social-graph MatchEdge for this pair.")]
+    InvalidMatchEdge,
+
+    #[msg("Match edge is not active.")]
+    MatchNotActive,
+
+    #[msg("Cannot record an interaction with yourself.")]
+    SelfInteraction,
+
+    #[msg("Negative interactions must be recorded by an attester via record_negative_interaction.")]
+    NegativeInteractionRequiresAttester,
+
+    #[msg("Invalid interaction type for this instruction.")]
+    InvalidInteractionType,
+
+    #[msg("Trust score account already uses the current layout.")]
+    AlreadyUpgraded,
+
+    #[msg("Trust score account is suspended or banned.")]
+    AccountSuspended,
+
+    #[msg("Suspension must end in the future.")]
+    InvalidSuspension,
+
+    #[msg("Only validated reports can be appealed.")]
+    ReportNotAppealable,
+
+    #[msg("Appeal cooldown has not elapsed.")]
+    AppealCooldown,
+
+    #[msg("Appeal has already been resolved.")]
+    AppealAlreadyResolved,
+
+    #[msg("Unauthorized. Only registered reviewers can resolve appeals.")]
+    UnauthorizedReviewer,
+
+    #[msg("Reviewer must be independent of the moderator who validated the report.")]
+    ReviewerNotIndependent,
+
+    #[msg("Appeal cooldown must not be negative.")]
+    InvalidAppealCooldown,
+
+    #[msg("Certificate validity must be between 1 slot and the maximum.")]
+    InvalidCertificateValidity,
+
+    #[msg("Score certificate has expired.")]
+    CertificateExpired,
+
+    #[msg("Score is below the required minimum.")]
+    ScoreBelowMinimum,
+
+    #[msg("Batch must contain between 1 and InteractionEntry::MAX_BATCH entries.")]
+    InvalidBatchSize,
+
+    #[msg("Each account may appear only once per batch.")]
+    DuplicateBatchEntry,
+
+    #[msg("Epoch and leaf count must be greater than zero and the snapshot must not be in the future.")]
+    InvalidEpochRoot,
+
+    #[msg("Trust score has already been settled for this or a later epoch.")]
+    StaleEpoch,
+
+    #[msg("Merkle proof does not match the epoch root.")]
+    InvalidMerkleProof,
+
+    #[msg("Account is not a valid trust-score-mini TrustAccount for this user.")]
+    InvalidMiniAccount,
+
+    #[msg("Account is not a valid ScoringConfig.")]
+    InvalidScoringConfigAccount,
+
+    #[msg("Every vouch received by the reported user must be passed.")]
+    IncompleteVouchAccounts,
+
+    #[msg("Trust score has pending reports and cannot be migrated.")]
+    PendingReports,
+
+    #[msg("Guardian has already vetoed this recovery.")]
+    RecoveryAlreadyVetoed,
+
+    #[msg("User has received the maximum number of vouches.")]
+    TooManyVouchesReceived,
+
+    #[msg("The appeal window for this report has closed.")]
+    AppealWindowClosed,
+
+    #[msg("The appeal window for this report is still open.")]
+    AppealWindowOpen,
+
+    #[msg("Report bond is not withdrawable.")]
+    BondNotWithdrawable,
+
+    #[msg("Epoch must be greater than the latest published epoch.")]
+    EpochNotIncreasing,
+
+    #[msg("Only the latest published epoch can be claimed.")]
+    EpochNotLatest,
+
+    #[msg("Trust score changed after the epoch snapshot.")]
+    ScoreChangedSinceSnapshot,
+
+    #[msg("Signer is not the program upgrade authority.")]
+    NotUpgradeAuthority,
+
+    #[msg("Match edge has not been accepted by both parties.")]
+    MatchNotAccepted,
+
+    #[msg("Account is not the interaction pair PDA for this owner and counterparty.")]
+    InvalidInteractionPairAccount,
+}
+
+// ==========================================
+// 事件 / Events
+// ==========================================
+
+#[event]
+pub struct TrustScoreInitialized {
+    pub owner: Pubkey,
+    pub initial_score: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrustScoreUpdated {
+    pub owner: Pubkey,
+    pub old_score: u16,
+    pub new_score: u16,
+    pub interaction_type: InteractionType,
+    pub counterparty: Option<Pubkey>,
+    pub timestamp: i64,
+    pub config_version: u32,
+}
+
+#[event]
+pub struct InteractionsBatchRecorded {
+    pub attester: Pubkey,
+    pub results: Vec<InteractionBatchResult>,
+    pub timestamp: i64,
+    pub config_version: u32,
+}
+
+#[event]
+pub struct TrustScoreMigratedFromMini {
+    pub owner: Pubkey,
+    pub mini_score: u64,
+    pub mini_total_interactions: u64,
+    pub base_score: u16,
+    pub timestamp: i64,
+    /// 是否合并到已有的 TrustScore
+    pub merged: bool,
+}
+
+#[event]
+pub struct EpochRootPublished {
+    pub epoch: u64,
+    pub root: [u8; 32],
+    pub leaf_count: u32,
+    pub publisher: Pubkey,
+    pub snapshot_at: i64,
+    pub timestamp: i64,
+    pub config_version: u32,
+}
+
+#[event]
+pub struct EpochScoreClaimed {
+    pub owner: Pubkey,
+    pub epoch: u64,
+    pub old_score: u16,
+    pub new_score: u16,
+    pub claimer: Pubkey,
+}
+
+#[event]
+pub struct ScoreCertificateIssued {
+    pub owner: Pubkey,
+    pub weighted_score: u16,
+    pub confidence_bps: u16,
+    pub expiry_slot: u64,
+    pub config_version: u32,
+}
+
+#[event]
+pub struct TrustScoreDecayed {
+    pub owner: Pubkey,
+    pub old_score: u16,
+    pub new_score: u16,
+    pub timestamp: i64,
+    pub config_version: u32,
+}
+
+#[event]
+pub struct LearningActivityRecorded {
+    pub owner: Pubkey,
+    pub cards_viewed: u16,
+    pub engagement_score: u8,
+    pub current_streak: u16,
+    pub timestamp: i64,
+    pub new_score: u16,
+    pub config_version: u32,
+}
+
+#[event]
+pub struct UserReported {
+    pub reporter: Pubkey,
     pub reported_user: Pubkey,
     pub total_reports: u16,
     pub new_score: u16,
+    pub bond_lamports: u64,
+}
+
+#[event]
+pub struct VouchCreated {
+    pub voucher: Pubkey,
+    pub vouchee: Pubkey,
+    pub weight: u16,
+    pub new_score: u16,
+    pub config_version: u32,
+}
+
+#[event]
+pub struct VouchRevoked {
+    pub voucher: Pubkey,
+    pub vouchee: Pubkey,
+    pub weight: u16,
+    pub new_score: u16,
+}
+
+#[event]
+pub struct VoucherPenalized {
+    pub voucher: Pubkey,
+    pub vouchee: Pubkey,
+    pub report: Pubkey,
+    pub new_score: u16,
+    pub config_version: u32,
+}
+
+#[event]
+pub struct TrustScoreMigrated {
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub base_score: u16,
+    pub via_recovery: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecoveryInitiated {
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub guardian: Pubkey,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct RecoveryApproved {
+    pub old_owner: Pubkey,
+    pub guardian: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct RecoveryVetoed {
+    pub old_owner: Pubkey,
+    pub guardian: Pubkey,
+    pub vetoes: u8,
+}
+
+#[event]
+pub struct ScoringConfigUpdated {
+    pub admin: Pubkey,
+    pub version: u32,
+    pub params: ScoringParams,
+}
+
+/// 统一的分数变动事件，按时间顺序回放即可重建任意用户的分数
+#[event]
+pub struct ScoreChanged {
+    pub owner: Pubkey,
+    pub old: u16,
+    pub new: u16,
+    pub cause: ScoreCause,
+    pub actor: Pubkey,
+    pub config_version: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AppealFiled {
+    pub appeal: Pubkey,
+    pub report: Pubkey,
+    pub appellant: Pubkey,
+    pub statement_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AppealResolved {
+    pub appeal: Pubkey,
+    pub report: Pubkey,
+    pub appellant: Pubkey,
+    pub reviewer: Pubkey,
+    pub status: AppealStatus,
+    pub restored_points: u16,
+    pub new_score: u16,
+    pub config_version: u32,
+}
+
+#[event]
+pub struct AccountStatusChanged {
+    pub owner: Pubkey,
+    pub moderator: Pubkey,
+    pub status: AccountStatus,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReportResolved {
+    pub report: Pubkey,
+    pub reporter: Pubkey,
+    pub reported_user: Pubkey,
+    pub moderator: Pubkey,
+    pub status: ReportStatus,
+    pub new_score: u16,
+    pub bond_lamports: u64,
+    pub config_version: u32,
+}
+
+#[event]
+pub struct AuthorityRegistryUpdated {
+    pub role: AuthorityRole,
+    pub authority: Pubkey,
+    pub added: bool,
 }
@@ -0,0 +1,255 @@
+/**
+ * 申诉测试
+ * 被举报人可对审核通过的举报申诉；复核员（不能是原审核员）裁决：
+ * 成立时恢复扣分、退还担保人连带扣分并罚没保证金，驳回时举报恢复为审核通过
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TrustScore } from "../target/types/trust_score";
+import { expect } from "chai";
+import {
+  createUser,
+  ensureAuthority,
+  ensureReportConfig,
+  ensureScoringConfig,
+  expectError,
+  findPda,
+  fundedKeypair,
+  overrideScoringParams,
+  registryPda,
+  reportConfigPda,
+  reportPda,
+  scoreHistoryPda,
+  trustScorePda,
+} from "./helpers";
+
+describe("appeals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.TrustScore as Program<TrustScore>;
+  const moderator = provider.wallet.publicKey;
+
+  let treasury: anchor.web3.PublicKey;
+  let reviewer: anchor.web3.Keypair;
+  let originalParams: Awaited<ReturnType<typeof overrideScoringParams>>;
+
+  const fetchTrustScore = (owner: anchor.web3.PublicKey) =>
+    program.account.trustScore.fetch(trustScorePda(program, owner));
+
+  const appealPda = (report: anchor.web3.PublicKey) => findPda(program, [Buffer.from("appeal"), report.toBuffer()]);
+
+  const reportUser = (reporter: anchor.web3.Keypair, reportedUser: anchor.web3.PublicKey) =>
+    program.methods
+      .reportUser("spam", Array(32).fill(3))
+      .accountsPartial({ reporter: reporter.publicKey, reportedUser })
+      .signers([reporter])
+      .rpc();
+
+  const validateReport = (report: anchor.web3.PublicKey, vouches: anchor.web3.AccountMeta[] = []) =>
+    program.methods
+      .validateReport()
+      .accountsPartial({ report, moderatorRegistry: registryPda(program, "moderator"), moderator, treasury })
+      .remainingAccounts(vouches)
+      .rpc();
+
+  // 新建被举报人（可选一名担保人），提交举报并审核通过
+  const validatedReport = async (reported?: anchor.web3.Keypair, withVoucher = false) => {
+    const reporter = await createUser(program);
+    const reportedUser = reported ?? (await createUser(program));
+    let voucher: anchor.web3.Keypair | undefined;
+    const vouches: anchor.web3.AccountMeta[] = [];
+
+    if (withVoucher) {
+      voucher = await createUser(program);
+      await program.methods
+        .vouchFor()
+        .accountsPartial({ voucher: voucher.publicKey, vouchee: reportedUser.publicKey })
+        .signers([voucher])
+        .rpc();
+      vouches.push(
+        {
+          pubkey: findPda(program, [Buffer.from("vouch"), voucher.publicKey.toBuffer(), reportedUser.publicKey.toBuffer()]),
+          isSigner: false,
+          isWritable: false,
+        },
+        { pubkey: trustScorePda(program, voucher.publicKey), isSigner: false, isWritable: true },
+        { pubkey: scoreHistoryPda(program, voucher.publicKey), isSigner: false, isWritable: true }
+      );
+    }
+
+    await reportUser(reporter, reportedUser.publicKey);
+    const report = reportPda(program, reporter.publicKey, reportedUser.publicKey);
+    await validateReport(report, vouches);
+
+    return { reporter, reported: reportedUser, voucher, report };
+  };
+
+  const fileAppeal = (appellant: anchor.web3.Keypair, report: anchor.web3.PublicKey) =>
+    program.methods
+      .fileAppeal(Array(32).fill(9))
+      .accountsPartial({ report, appellant: appellant.publicKey })
+      .signers([appellant])
+      .rpc();
+
+  const resolveAppeal = (
+    report: anchor.web3.PublicKey,
+    overturn: boolean,
+    signer: anchor.web3.Keypair | null = reviewer,
+    vouchers: anchor.web3.PublicKey[] = []
+  ) => {
+    const builder = program.methods
+      .resolveAppeal(overturn)
+      .accountsPartial({
+        report,
+        reviewerRegistry: registryPda(program, "reviewer"),
+        reviewer: signer ? signer.publicKey : moderator,
+        treasury,
+      })
+      .remainingAccounts(
+        vouchers.flatMap((voucher) => [
+          { pubkey: trustScorePda(program, voucher), isSigner: false, isWritable: true },
+          { pubkey: scoreHistoryPda(program, voucher), isSigner: false, isWritable: true },
+        ])
+      );
+    return signer ? builder.signers([signer]).rpc() : builder.rpc();
+  };
+
+  const setAppealCooldown = async (seconds: number) => {
+    const config = await program.account.reportConfig.fetch(reportConfigPda(program));
+    await program.methods
+      .updateReportConfig(config.bondLamports, config.treasury, new anchor.BN(seconds))
+      .accountsPartial({ reportConfig: reportConfigPda(program), admin: moderator })
+      .rpc();
+  };
+
+  before(async () => {
+    await ensureScoringConfig(program);
+    await ensureAuthority(program, "moderator");
+    await ensureAuthority(program, "reviewer");
+    ({ treasury } = await ensureReportConfig(program));
+
+    // provider 钱包同时是版主，复核需要另一名复核员
+    reviewer = await fundedKeypair(program, 1);
+    await program.methods
+      .addAuthority(reviewer.publicKey)
+      .accountsPartial({ authorityRegistry: registryPda(program, "reviewer"), admin: moderator })
+      .rpc();
+
+    // 新用户分数低于默认担保门槛，测试期间放开
+    originalParams = await overrideScoringParams(program, { minVoucherScore: 0 });
+  });
+
+  after(async () => {
+    await overrideScoringParams(program, originalParams);
+  });
+
+  it("待审核的举报不能申诉", async () => {
+    const reporter = await createUser(program);
+    const reported = await createUser(program);
+    await reportUser(reporter, reported.publicKey);
+
+    await expectError(
+      fileAppeal(reported, reportPda(program, reporter.publicKey, reported.publicKey)),
+      "ReportNotAppealable"
+    );
+  });
+
+  it("只有被举报人可以申诉", async () => {
+    const { reporter, report } = await validatedReport();
+    await expectError(fileAppeal(reporter, report), "Unauthorized");
+  });
+
+  it("申诉后举报进入申诉中，复核员不能是原审核员", async () => {
+    const { reported, report } = await validatedReport();
+
+    await fileAppeal(reported, report);
+
+    expect((await program.account.report.fetch(report)).status).to.deep.equal({ appealed: {} });
+    const appeal = await program.account.appeal.fetch(appealPda(report));
+    expect(appeal.status).to.deep.equal({ pending: {} });
+    expect(appeal.appellant.equals(reported.publicKey)).to.be.true;
+    expect((await fetchTrustScore(reported.publicKey)).lastAppealAt.toNumber()).to.equal(
+      appeal.filedAt.toNumber()
+    );
+
+    // provider 钱包审核了该举报，即使在复核员名单中也不能复核
+    await expectError(resolveAppeal(report, true, null), "ReviewerNotIndependent");
+
+    const outsider = await fundedKeypair(program, 1);
+    await expectError(resolveAppeal(report, true, outsider), "UnauthorizedReviewer");
+  });
+
+  it("申诉驳回时举报恢复为审核通过，分数不变", async () => {
+    const { reported, report } = await validatedReport();
+    await fileAppeal(reported, report);
+    const before = await fetchTrustScore(reported.publicKey);
+
+    await resolveAppeal(report, false);
+
+    const appeal = await program.account.appeal.fetch(appealPda(report));
+    expect(appeal.status).to.deep.equal({ denied: {} });
+    expect(appeal.resolvedBy!.equals(reviewer.publicKey)).to.be.true;
+    expect((await program.account.report.fetch(report)).status).to.deep.equal({ validated: {} });
+
+    const after = await fetchTrustScore(reported.publicKey);
+    expect(after.baseScore).to.equal(before.baseScore);
+    expect(after.reportsValidated).to.equal(before.reportsValidated);
+
+    // 已裁决的申诉不能再次裁决
+    await expectError(resolveAppeal(report, true), "AppealAlreadyResolved");
+  });
+
+  it("申诉成立时恢复扣分、退还担保人扣分并罚没保证金", async () => {
+    const { reported, voucher, report } = await validatedReport(undefined, true);
+    await fileAppeal(reported, report);
+
+    const validated = await program.account.report.fetch(report);
+    const reportedBefore = await fetchTrustScore(reported.publicKey);
+    const voucherBefore = await fetchTrustScore(voucher!.publicKey);
+    const treasuryBefore = await provider.connection.getBalance(treasury);
+
+    // 担保人账户必须按举报记录传入
+    await expectError(resolveAppeal(report, true), "InvalidRemainingAccounts");
+
+    await resolveAppeal(report, true, reviewer, [voucher!.publicKey]);
+
+    const resolved = await program.account.report.fetch(report);
+    expect(resolved.status).to.deep.equal({ overturned: {} });
+    expect(resolved.bondSettled).to.be.true;
+    expect(await provider.connection.getBalance(treasury)).to.equal(
+      treasuryBefore + resolved.bondLamports.toNumber()
+    );
+    expect((await program.account.appeal.fetch(appealPda(report))).status).to.deep.equal({ granted: {} });
+
+    const reportedAfter = await fetchTrustScore(reported.publicKey);
+    expect(reportedAfter.baseScore).to.equal(reportedBefore.baseScore + validated.penaltyApplied);
+    expect(reportedAfter.reportsValidated).to.equal(reportedBefore.reportsValidated - 1);
+
+    const voucherAfter = await fetchTrustScore(voucher!.publicKey);
+    expect(voucherAfter.baseScore).to.equal(voucherBefore.baseScore + validated.voucherPenalties[0].points);
+
+    // 已裁决的申诉不能再次裁决
+    await expectError(resolveAppeal(report, true, reviewer, [voucher!.publicKey]), "AppealAlreadyResolved");
+  });
+
+  describe("申诉冷却期", () => {
+    before(async () => {
+      await setAppealCooldown(3600);
+    });
+
+    after(async () => {
+      await setAppealCooldown(0);
+    });
+
+    it("冷却期内不能对另一条举报再次申诉", async () => {
+      const { reported, report: first } = await validatedReport();
+      const { report: second } = await validatedReport(reported);
+
+      await fileAppeal(reported, first);
+      await expectError(fileAppeal(reported, second), "AppealCooldown");
+      expect((await program.account.report.fetch(second)).status).to.deep.equal({ validated: {} });
+    });
+  });
+});
@@ -0,0 +1,136 @@
+/**
+ * ed25519 证明测试
+ * 开启强制模式后，学习记录须附带已注册证明方对 (owner, 数据, nonce) 的 ed25519 签名
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TrustScore } from "../target/types/trust_score";
+import { expect } from "chai";
+import {
+  createUser,
+  ensureAuthority,
+  ensureScoringConfig,
+  expectError,
+  expectFailure,
+  registryPda,
+  trustScorePda,
+} from "./helpers";
+
+const { Ed25519Program, Keypair, SYSVAR_INSTRUCTIONS_PUBKEY } = anchor.web3;
+
+// ed25519 指令数据中公钥的起始偏移（紧跟 16 字节的偏移量头部）
+const ED25519_PUBLIC_KEY_OFFSET = 16;
+
+const CARDS_VIEWED = 5;
+const ENGAGEMENT_SCORE = 70;
+
+describe("attestation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.TrustScore as Program<TrustScore>;
+  const admin = provider.wallet.publicKey;
+  const attesterRegistry = registryPda(program, "attester");
+
+  const attester = Keypair.generate();
+  let user: anchor.web3.Keypair;
+
+  const learningMessage = (owner: anchor.web3.PublicKey, nonce: number) => {
+    const cards = Buffer.alloc(2);
+    cards.writeUInt16LE(CARDS_VIEWED);
+    const nonceLe = Buffer.alloc(8);
+    nonceLe.writeBigUInt64LE(BigInt(nonce));
+    return Buffer.concat([
+      Buffer.from("swiv:record_learning"),
+      owner.toBuffer(),
+      cards,
+      Buffer.from([ENGAGEMENT_SCORE]),
+      nonceLe,
+    ]);
+  };
+
+  const signLearning = (signer: anchor.web3.Keypair, nonce: number) =>
+    Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: signer.secretKey,
+      message: learningMessage(user.publicKey, nonce),
+    });
+
+  const recordLearning = (preInstructions: anchor.web3.TransactionInstruction[]) =>
+    program.methods
+      .recordLearningActivity(CARDS_VIEWED, ENGAGEMENT_SCORE)
+      .accountsPartial({
+        trustScore: trustScorePda(program, user.publicKey),
+        owner: user.publicKey,
+        attester: null,
+        instructionsSysvar: preInstructions.length ? SYSVAR_INSTRUCTIONS_PUBKEY : null,
+      })
+      .preInstructions(preInstructions)
+      .signers([user])
+      .rpc();
+
+  const fetchNonce = async () =>
+    (await program.account.trustScore.fetch(trustScorePda(program, user.publicKey))).attestationNonce.toNumber();
+
+  const setEnforcement = (enforced: boolean) =>
+    program.methods
+      .setAuthorityEnforcement(enforced)
+      .accountsPartial({ authorityRegistry: attesterRegistry, admin })
+      .rpc();
+
+  before(async () => {
+    await ensureScoringConfig(program);
+    await ensureAuthority(program, "attester");
+
+    await program.methods
+      .addAuthority(attester.publicKey)
+      .accountsPartial({ authorityRegistry: attesterRegistry, admin })
+      .rpc();
+    await setEnforcement(true);
+
+    user = await createUser(program);
+  });
+
+  after(async () => {
+    // 强制模式是全局的，恢复后其他测试文件不受影响
+    await setEnforcement(false);
+    await program.methods
+      .removeAuthority(attester.publicKey)
+      .accountsPartial({ authorityRegistry: attesterRegistry, admin })
+      .rpc();
+  });
+
+  it("应该接受已注册证明方的 ed25519 签名并推进 nonce", async () => {
+    const nonce = await fetchNonce();
+    await recordLearning([signLearning(attester, nonce)]);
+
+    expect(await fetchNonce()).to.equal(nonce + 1);
+  });
+
+  it("应该拒绝重放已使用 nonce 的签名", async () => {
+    const nonce = await fetchNonce();
+    await expectError(recordLearning([signLearning(attester, nonce - 1)]), "InvalidAttestation");
+
+    expect(await fetchNonce()).to.equal(nonce);
+  });
+
+  it("应该拒绝未注册证明方的签名", async () => {
+    const outsider = Keypair.generate();
+    await expectError(recordLearning([signLearning(outsider, await fetchNonce())]), "UnauthorizedAttester");
+  });
+
+  it("应该拒绝缺少证明的记录", async () => {
+    await expectError(recordLearning([]), "AttestationRequired");
+  });
+
+  it("伪造的签名应该被 ed25519 预编译拒绝", async () => {
+    const nonce = await fetchNonce();
+
+    // 由未注册的密钥签名，再把公钥替换为已注册证明方
+    const forged = signLearning(Keypair.generate(), nonce);
+    attester.publicKey.toBuffer().copy(forged.data, ED25519_PUBLIC_KEY_OFFSET);
+
+    await expectFailure(recordLearning([forged]));
+    expect(await fetchNonce()).to.equal(nonce);
+  });
+});
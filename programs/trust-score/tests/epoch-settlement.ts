@@ -0,0 +1,143 @@
+/**
+ * 纪元结算测试
+ * 只能结算最新纪元、快照后有变动的账户与非正常账户不能结算、分项随总分同步
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TrustScore } from "../target/types/trust_score";
+import { expect } from "chai";
+import {
+  chainTime,
+  createUser,
+  ensureAuthority,
+  ensureScoringConfig,
+  expectError,
+  findPda,
+  hashPair,
+  leafHash,
+  registryPda,
+  scoreHistoryPda,
+  trustScorePda,
+} from "./helpers";
+
+describe("epoch-settlement", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.TrustScore as Program<TrustScore>;
+  const attester = provider.wallet.publicKey;
+
+  let alice: anchor.web3.Keypair;
+  let bob: anchor.web3.Keypair;
+  let epoch: number;
+
+  const epochRootPda = (epoch: number) =>
+    findPda(program, [Buffer.from("epoch_root"), new anchor.BN(epoch).toArrayLike(Buffer, "le", 8)]);
+
+  const publish = (epoch: number, root: Buffer, leafCount: number, snapshotAt: number) =>
+    program.methods
+      .publishEpochRoot(new anchor.BN(epoch), [...root], leafCount, new anchor.BN(snapshotAt))
+      .accountsPartial({ epochRoot: epochRootPda(epoch), attester })
+      .rpc();
+
+  const claim = (epoch: number, user: anchor.web3.PublicKey, score: number, proof: Buffer[]) =>
+    program.methods
+      .claimEpochScore(score, proof.map((node) => [...node]))
+      .accountsPartial({
+        epochRoot: epochRootPda(epoch),
+        trustScore: trustScorePda(program, user),
+        scoreHistory: scoreHistoryPda(program, user),
+        claimer: provider.wallet.publicKey,
+      })
+      .rpc();
+
+  before(async () => {
+    await ensureScoringConfig(program);
+    await ensureAuthority(program, "attester");
+    await ensureAuthority(program, "moderator");
+
+    alice = await createUser(program);
+    bob = await createUser(program);
+
+    // 纪元状态是全局单例，从当前最新纪元之后开始
+    const epochState = await program.account.epochState.fetchNullable(
+      findPda(program, [Buffer.from("epoch_state")])
+    );
+    epoch = (epochState ? epochState.latestEpoch.toNumber() : 0) + 1;
+  });
+
+  it("应该结算最新纪元并同步分项", async () => {
+    const aliceLeaf = leafHash(alice.publicKey, 300, epoch);
+    const bobLeaf = leafHash(bob.publicKey, 250, epoch);
+    await publish(epoch, hashPair(aliceLeaf, bobLeaf), 2, await chainTime(program));
+
+    await claim(epoch, alice.publicKey, 300, [bobLeaf]);
+
+    const trustScore = await program.account.trustScore.fetch(trustScorePda(program, alice.publicKey));
+    expect(trustScore.baseScore).to.equal(300);
+    expect(trustScore.lastSettledEpoch.toNumber()).to.equal(epoch);
+    // 初始分项均为 100，整体平移 +200
+    expect(trustScore.subScores).to.deep.equal({ social: 300, learning: 300, mentoring: 300, safety: 300 });
+  });
+
+  it("应该拒绝重复结算", async () => {
+    const bobLeaf = leafHash(bob.publicKey, 250, epoch);
+    await expectError(claim(epoch, alice.publicKey, 300, [bobLeaf]), "StaleEpoch");
+  });
+
+  it("应该拒绝篡改分数的证明", async () => {
+    const aliceLeaf = leafHash(alice.publicKey, 300, epoch);
+    await expectError(claim(epoch, bob.publicKey, 900, [aliceLeaf]), "InvalidMerkleProof");
+  });
+
+  it("新纪元发布后应该拒绝结算旧纪元", async () => {
+    const aliceLeaf = leafHash(alice.publicKey, 300, epoch);
+    const bobLeaf = leafHash(bob.publicKey, 250, epoch);
+
+    await publish(epoch + 1, leafHash(bob.publicKey, 260, epoch + 1), 1, await chainTime(program));
+    await expectError(claim(epoch, bob.publicKey, 250, [aliceLeaf]), "EpochNotLatest");
+
+    // 纪元号必须递增
+    await expectError(publish(epoch + 1, hashPair(aliceLeaf, bobLeaf), 2, await chainTime(program)), "EpochNotIncreasing");
+
+    await claim(epoch + 1, bob.publicKey, 260, []);
+    epoch += 1;
+  });
+
+  it("快照之后分数有变动的账户应该拒绝结算", async () => {
+    const snapshotAt = (await chainTime(program)) - 1;
+
+    await program.methods
+      .recordLearningActivity(5, 90)
+      .accountsPartial({
+        trustScore: trustScorePda(program, alice.publicKey),
+        scoreHistory: scoreHistoryPda(program, alice.publicKey),
+        owner: alice.publicKey,
+        attester: null,
+        instructionsSysvar: null,
+      })
+      .signers([alice])
+      .rpc();
+
+    epoch += 1;
+    await publish(epoch, leafHash(alice.publicKey, 500, epoch), 1, snapshotAt);
+    await expectError(claim(epoch, alice.publicKey, 500, []), "ScoreChangedSinceSnapshot");
+  });
+
+  it("暂停中的账户应该拒绝结算", async () => {
+    const until = (await chainTime(program)) + 3600;
+    await program.methods
+      .setAccountStatus({ suspended: { until: new anchor.BN(until) } })
+      .accountsPartial({
+        trustScore: trustScorePda(program, bob.publicKey),
+        moderatorRegistry: registryPda(program, "moderator"),
+        moderator: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    epoch += 1;
+    await publish(epoch, leafHash(bob.publicKey, 400, epoch), 1, await chainTime(program));
+    await expectError(claim(epoch, bob.publicKey, 400, []), "AccountSuspended");
+  });
+});
@@ -0,0 +1,238 @@
+//! CPI 守卫测试
+//! require_min_trust 的账户校验与门槛、meets_min_trust 的门槛
+#![cfg(feature = "cpi")]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::clock::Clock;
+use solana_sysvar::program_stubs::{set_syscall_stubs, SyscallStubs};
+use std::sync::Once;
+use trust_score::{
+    guard, AccountStatus, ErrorCode, ScoringConfig, ScoringParams, SubScores, TrustScore,
+};
+
+const NOW: i64 = 1_700_000_000;
+
+/// 非 SBF 目标上 Clock::get 默认不可用，固定为 NOW
+struct FixedClock;
+
+impl SyscallStubs for FixedClock {
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            unix_timestamp: NOW,
+            ..Clock::default()
+        };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        0
+    }
+}
+
+fn init_clock() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        set_syscall_stubs(Box::new(FixedClock));
+    });
+}
+
+fn trust_score(owner: Pubkey, base_score: u16, status: AccountStatus) -> TrustScore {
+    let (_, bump) =
+        Pubkey::find_program_address(&[b"trust_score", owner.as_ref()], &trust_score::ID);
+    TrustScore {
+        owner,
+        base_score,
+        total_interactions: 0,
+        positive_interactions: 0,
+        reports_received: 0,
+        reports_validated: 0,
+        learning_streak: 0,
+        last_active: NOW,
+        bump,
+        attestation_nonce: 0,
+        last_decay_at: NOW,
+        outstanding_vouches: 0,
+        vouches_received: 0,
+        sub_scores: SubScores {
+            social: base_score,
+            learning: base_score,
+            mentoring: base_score,
+            safety: base_score,
+        },
+        created_at: 0,
+        status,
+        last_appeal_at: 0,
+        last_settled_epoch: 0,
+        pending_reports: 0,
+    }
+}
+
+fn scoring_config() -> ScoringConfig {
+    let (_, bump) = Pubkey::find_program_address(&[b"scoring_config"], &trust_score::ID);
+    ScoringConfig {
+        admin: Pubkey::new_unique(),
+        version: 1,
+        params: ScoringParams::default(),
+        bump,
+    }
+}
+
+/// 序列化后的账户（地址、owner 程序、数据）
+struct Fixture {
+    key: Pubkey,
+    owner: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+}
+
+impl Fixture {
+    fn new<T: AccountSerialize>(key: Pubkey, owner: Pubkey, account: &T) -> Self {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        Self {
+            key,
+            owner,
+            lamports: 1,
+            data,
+        }
+    }
+
+    fn trust_score(account: &TrustScore) -> Self {
+        let key = Pubkey::find_program_address(
+            &[b"trust_score", account.owner.as_ref()],
+            &trust_score::ID,
+        )
+        .0;
+        Self::new(key, trust_score::ID, account)
+    }
+
+    fn scoring_config(account: &ScoringConfig) -> Self {
+        let key = Pubkey::find_program_address(&[b"scoring_config"], &trust_score::ID).0;
+        Self::new(key, trust_score::ID, account)
+    }
+
+    fn info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.key,
+            false,
+            false,
+            &mut self.lamports,
+            &mut self.data,
+            &self.owner,
+            false,
+            0,
+        )
+    }
+}
+
+fn require_min_trust(
+    score: &mut Fixture,
+    config: &mut Fixture,
+    user: &Pubkey,
+    min_score: u16,
+) -> Result<u16> {
+    init_clock();
+    guard::require_min_trust(&score.info(), &config.info(), user, min_score)
+}
+
+#[test]
+fn require_min_trust_returns_weighted_score_at_threshold() {
+    let user = Pubkey::new_unique();
+    let mut score = Fixture::trust_score(&trust_score(user, 300, AccountStatus::Active));
+    let mut config = Fixture::scoring_config(&scoring_config());
+
+    let weighted = require_min_trust(&mut score, &mut config, &user, 0).unwrap();
+    assert!(weighted > 0);
+    assert_eq!(
+        require_min_trust(&mut score, &mut config, &user, weighted).unwrap(),
+        weighted
+    );
+    assert_eq!(
+        require_min_trust(&mut score, &mut config, &user, weighted + 1).unwrap_err(),
+        ErrorCode::ScoreBelowMinimum.into()
+    );
+}
+
+#[test]
+fn require_min_trust_rejects_inactive_accounts() {
+    let user = Pubkey::new_unique();
+    let mut config = Fixture::scoring_config(&scoring_config());
+
+    for status in [
+        AccountStatus::Suspended { until: NOW + 1 },
+        AccountStatus::Banned,
+    ] {
+        let mut score = Fixture::trust_score(&trust_score(user, 300, status));
+        assert_eq!(
+            require_min_trust(&mut score, &mut config, &user, 0).unwrap_err(),
+            ErrorCode::AccountSuspended.into()
+        );
+    }
+
+    // 已到期的暂停视为正常
+    let mut score = Fixture::trust_score(&trust_score(
+        user,
+        300,
+        AccountStatus::Suspended { until: NOW },
+    ));
+    assert!(require_min_trust(&mut score, &mut config, &user, 0).is_ok());
+}
+
+#[test]
+fn require_min_trust_rejects_foreign_accounts() {
+    let user = Pubkey::new_unique();
+    let account = trust_score(user, 300, AccountStatus::Active);
+    let mut config = Fixture::scoring_config(&scoring_config());
+
+    // 不属于 trust-score 程序
+    let mut forged = Fixture::trust_score(&account);
+    forged.owner = Pubkey::new_unique();
+    assert_eq!(
+        require_min_trust(&mut forged, &mut config, &user, 0).unwrap_err(),
+        ErrorCode::InvalidTrustScoreAccount.into()
+    );
+
+    // 其他用户的账户
+    let mut score = Fixture::trust_score(&account);
+    assert_eq!(
+        require_min_trust(&mut score, &mut config, &Pubkey::new_unique(), 0).unwrap_err(),
+        ErrorCode::InvalidTrustScoreAccount.into()
+    );
+
+    // 数据正确但地址不是 PDA
+    let mut moved = Fixture::new(Pubkey::new_unique(), trust_score::ID, &account);
+    assert_eq!(
+        require_min_trust(&mut moved, &mut config, &user, 0).unwrap_err(),
+        ErrorCode::InvalidTrustScoreAccount.into()
+    );
+
+    // 评分参数不属于 trust-score 程序
+    let mut forged_config = Fixture::scoring_config(&scoring_config());
+    forged_config.owner = Pubkey::new_unique();
+    assert_eq!(
+        require_min_trust(&mut score, &mut forged_config, &user, 0).unwrap_err(),
+        ErrorCode::InvalidScoringConfigAccount.into()
+    );
+
+    // 评分参数不是配置 PDA
+    let mut fake_config = Fixture::new(Pubkey::new_unique(), trust_score::ID, &scoring_config());
+    assert_eq!(
+        require_min_trust(&mut score, &mut fake_config, &user, 0).unwrap_err(),
+        ErrorCode::InvalidScoringConfigAccount.into()
+    );
+}
+
+#[test]
+fn meets_min_trust_follows_threshold_and_status() {
+    init_clock();
+    let user = Pubkey::new_unique();
+    let config = scoring_config();
+    let active = trust_score(user, 300, AccountStatus::Active);
+
+    let mut score = Fixture::trust_score(&active);
+    let mut config_account = Fixture::scoring_config(&config);
+    let weighted = require_min_trust(&mut score, &mut config_account, &user, 0).unwrap();
+
+    assert!(guard::meets_min_trust(&active, &config, weighted));
+    assert!(!guard::meets_min_trust(&active, &config, weighted + 1));
+
+    let suspended = trust_score(user, 300, AccountStatus::Suspended { until: NOW + 1 });
+    assert!(!guard::meets_min_trust(&suspended, &config, 0));
+}
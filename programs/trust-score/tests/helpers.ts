@@ -0,0 +1,205 @@
+/**
+ * TrustScore 程序测试的公共工具
+ * 协议配置（评分参数、授权方注册表）是全局单例，各测试文件共用同一份，已存在时跳过初始化
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TrustScore } from "../target/types/trust_score";
+import { createHash } from "crypto";
+import { expect } from "chai";
+
+const { PublicKey, Keypair, LAMPORTS_PER_SOL } = anchor.web3;
+
+export const findPda = (program: Program<TrustScore>, seeds: (Buffer | Uint8Array)[]) =>
+  PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+export const trustScorePda = (program: Program<TrustScore>, user: anchor.web3.PublicKey) =>
+  findPda(program, [Buffer.from("trust_score"), user.toBuffer()]);
+
+export const scoreHistoryPda = (program: Program<TrustScore>, user: anchor.web3.PublicKey) =>
+  findPda(program, [Buffer.from("score_history"), user.toBuffer()]);
+
+export const registryPda = (program: Program<TrustScore>, role: "attester" | "moderator" | "reviewer") =>
+  findPda(program, [Buffer.from("authority_registry"), Buffer.from(role)]);
+
+/**
+ * 程序的 ProgramData 账户（升级权限所在），初始化全局配置时需要传入
+ */
+export const programDataPda = (program: Program<TrustScore>) =>
+  PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  )[0];
+
+export async function accountExists(program: Program<TrustScore>, address: anchor.web3.PublicKey) {
+  return (await program.provider.connection.getAccountInfo(address)) !== null;
+}
+
+/**
+ * 初始化评分参数配置（已存在则跳过）
+ * 只有程序升级权限（即 provider 钱包）可以初始化
+ */
+export async function ensureScoringConfig(program: Program<TrustScore>) {
+  const admin = program.provider.publicKey!;
+  if (!(await accountExists(program, findPda(program, [Buffer.from("scoring_config")])))) {
+    await program.methods
+      .initializeScoringConfig()
+      .accountsPartial({ admin, program: program.programId, programData: programDataPda(program) })
+      .rpc();
+  }
+}
+
+/**
+ * 临时修改评分参数，返回修改前的参数以便测试结束后恢复
+ */
+export async function overrideScoringParams(program: Program<TrustScore>, overrides: Record<string, unknown>) {
+  const admin = program.provider.publicKey!;
+  const scoringConfig = findPda(program, [Buffer.from("scoring_config")]);
+  const { params } = await program.account.scoringConfig.fetch(scoringConfig);
+
+  await program.methods
+    .updateScoringConfig({ ...params, ...overrides } as any)
+    .accountsPartial({ scoringConfig, admin })
+    .rpc();
+
+  return params;
+}
+
+export const reportConfigPda = (program: Program<TrustScore>) =>
+  findPda(program, [Buffer.from("report_config")]);
+
+export const bondVaultPda = (program: Program<TrustScore>) =>
+  findPda(program, [Buffer.from("bond_vault")]);
+
+export const reportPda = (
+  program: Program<TrustScore>,
+  reporter: anchor.web3.PublicKey,
+  reportedUser: anchor.web3.PublicKey
+) => findPda(program, [Buffer.from("report"), reporter.toBuffer(), reportedUser.toBuffer()]);
+
+// 测试使用的举报保证金与金库
+export const REPORT_BOND_LAMPORTS = 0.01 * LAMPORTS_PER_SOL;
+const reportTreasury = Keypair.generate().publicKey;
+
+/**
+ * 初始化举报配置及保证金托管账户（已存在则跳过），返回当前配置
+ * 只有程序升级权限（即 provider 钱包）可以初始化
+ */
+export async function ensureReportConfig(program: Program<TrustScore>) {
+  const admin = program.provider.publicKey!;
+  const reportConfig = reportConfigPda(program);
+
+  if (!(await accountExists(program, reportConfig))) {
+    await program.methods
+      .initializeReportConfig(new anchor.BN(REPORT_BOND_LAMPORTS), reportTreasury, new anchor.BN(0))
+      .accountsPartial({ admin, program: program.programId, programData: programDataPda(program) })
+      .rpc();
+  }
+
+  return program.account.reportConfig.fetch(reportConfig);
+}
+
+/**
+ * 初始化某个角色的授权方注册表，并将 provider 钱包注册为该角色（已存在则跳过）
+ */
+export async function ensureAuthority(
+  program: Program<TrustScore>,
+  role: "attester" | "moderator" | "reviewer"
+) {
+  const admin = program.provider.publicKey!;
+  const registry = registryPda(program, role);
+
+  if (!(await accountExists(program, registry))) {
+    // 只有程序升级权限（anchor test 部署时即 provider 钱包）可以初始化
+    await program.methods
+      .initializeAuthorityRegistry({ [role]: {} } as any)
+      .accountsPartial({ admin, program: program.programId, programData: programDataPda(program) })
+      .rpc();
+  }
+
+  const { authorities } = await program.account.authorityRegistry.fetch(registry);
+  if (!authorities.some((key) => key.equals(admin))) {
+    await program.methods
+      .addAuthority(admin)
+      .accountsPartial({ authorityRegistry: registry, admin })
+      .rpc();
+  }
+}
+
+/**
+ * 生成并注资一个新钱包
+ */
+export async function fundedKeypair(program: Program<TrustScore>, sol = 2) {
+  const keypair = Keypair.generate();
+  const connection = program.provider.connection;
+
+  const signature = await connection.requestAirdrop(keypair.publicKey, sol * LAMPORTS_PER_SOL);
+  await connection.confirmTransaction(signature);
+
+  return keypair;
+}
+
+/**
+ * 创建并注资一个新用户，初始化其 TrustScore
+ */
+export async function createUser(program: Program<TrustScore>) {
+  const user = await fundedKeypair(program);
+
+  await program.methods
+    .initializeTrustScore()
+    .accounts({ owner: user.publicKey })
+    .signers([user])
+    .rpc();
+
+  return user;
+}
+
+/**
+ * 当前区块时间（秒）
+ */
+export async function chainTime(program: Program<TrustScore>) {
+  const connection = program.provider.connection;
+  return (await connection.getBlockTime(await connection.getSlot()))!;
+}
+
+/**
+ * 断言交易因指定的程序错误失败
+ */
+export async function expectError(promise: Promise<unknown>, code: string) {
+  try {
+    await promise;
+  } catch (err) {
+    expect((err as anchor.AnchorError).error?.errorCode?.code).to.equal(code);
+    return;
+  }
+  expect.fail(`应该抛出 ${code}`);
+}
+
+/**
+ * 断言交易失败（非程序错误，如 ed25519 预编译验签失败）
+ */
+export async function expectFailure(promise: Promise<unknown>) {
+  try {
+    await promise;
+  } catch (err) {
+    return err;
+  }
+  expect.fail("交易应该失败");
+}
+
+// 与 crates/trust-score-merkle 的叶子、节点编码一致
+const sha256 = (...parts: Buffer[]) => createHash("sha256").update(Buffer.concat(parts)).digest();
+
+export function leafHash(owner: anchor.web3.PublicKey, score: number, epoch: number) {
+  const scoreLe = Buffer.alloc(2);
+  scoreLe.writeUInt16LE(score);
+  const epochLe = Buffer.alloc(8);
+  epochLe.writeBigUInt64LE(BigInt(epoch));
+  return sha256(Buffer.from([0]), owner.toBuffer(), scoreLe, epochLe);
+}
+
+export function hashPair(a: Buffer, b: Buffer) {
+  const [left, right] = Buffer.compare(a, b) <= 0 ? [a, b] : [b, a];
+  return sha256(Buffer.from([1]), left, right);
+}
@@ -0,0 +1,129 @@
+/**
+ * 全局配置初始化测试
+ * 配置与注册表只能由程序升级权限初始化，防止部署后被抢先初始化
+ *
+ * 必须在其他测试文件之前运行（Anchor.toml 中通过 --file 先行加载），
+ * 否则配置已被其他测试初始化，抢先初始化的路径无法覆盖
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TrustScore } from "../target/types/trust_score";
+import { expect } from "chai";
+import {
+  accountExists,
+  bondVaultPda,
+  ensureAuthority,
+  ensureReportConfig,
+  ensureScoringConfig,
+  expectError,
+  findPda,
+  fundedKeypair,
+  programDataPda,
+  registryPda,
+  REPORT_BOND_LAMPORTS,
+  reportConfigPda,
+} from "./helpers";
+
+describe("initialization", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.TrustScore as Program<TrustScore>;
+
+  let squatter: anchor.web3.Keypair;
+
+  before(async () => {
+    squatter = await fundedKeypair(program, 1);
+  });
+
+  describe("authority registry", () => {
+    it("应该拒绝非升级权限初始化注册表", async () => {
+      const registry = registryPda(program, "reviewer");
+      expect(await accountExists(program, registry), "注册表已被其他测试初始化").to.be.false;
+
+      await expectError(
+        program.methods
+          .initializeAuthorityRegistry({ reviewer: {} })
+          .accountsPartial({
+            admin: squatter.publicKey,
+            program: program.programId,
+            programData: programDataPda(program),
+          })
+          .signers([squatter])
+          .rpc(),
+        "NotUpgradeAuthority"
+      );
+      expect(await accountExists(program, registry)).to.be.false;
+    });
+
+    it("应该允许升级权限初始化注册表", async () => {
+      await ensureAuthority(program, "reviewer");
+
+      const registry = await program.account.authorityRegistry.fetch(registryPda(program, "reviewer"));
+      expect(registry.admin.equals(provider.wallet.publicKey)).to.be.true;
+      expect(registry.enforced).to.be.false;
+    });
+  });
+
+  describe("scoring config", () => {
+    const scoringConfigPda = () => findPda(program, [Buffer.from("scoring_config")]);
+
+    it("应该拒绝非升级权限初始化评分参数", async () => {
+      expect(await accountExists(program, scoringConfigPda()), "评分参数已被其他测试初始化").to.be.false;
+
+      await expectError(
+        program.methods
+          .initializeScoringConfig()
+          .accountsPartial({
+            admin: squatter.publicKey,
+            program: program.programId,
+            programData: programDataPda(program),
+          })
+          .signers([squatter])
+          .rpc(),
+        "NotUpgradeAuthority"
+      );
+      expect(await accountExists(program, scoringConfigPda())).to.be.false;
+    });
+
+    it("应该允许升级权限初始化评分参数", async () => {
+      await ensureScoringConfig(program);
+
+      const scoringConfig = await program.account.scoringConfig.fetch(scoringConfigPda());
+      expect(scoringConfig.admin.equals(provider.wallet.publicKey)).to.be.true;
+      expect(scoringConfig.version).to.equal(1);
+    });
+  });
+
+  describe("report config", () => {
+    it("应该拒绝非升级权限初始化举报配置", async () => {
+      const reportConfig = reportConfigPda(program);
+      expect(await accountExists(program, reportConfig), "举报配置已被其他测试初始化").to.be.false;
+
+      // 抢先初始化者试图把自己设为管理员和金库
+      await expectError(
+        program.methods
+          .initializeReportConfig(new anchor.BN(REPORT_BOND_LAMPORTS), squatter.publicKey, new anchor.BN(0))
+          .accountsPartial({
+            admin: squatter.publicKey,
+            program: program.programId,
+            programData: programDataPda(program),
+          })
+          .signers([squatter])
+          .rpc(),
+        "NotUpgradeAuthority"
+      );
+      expect(await accountExists(program, reportConfig)).to.be.false;
+      expect(await accountExists(program, bondVaultPda(program))).to.be.false;
+    });
+
+    it("应该允许升级权限初始化举报配置", async () => {
+      const reportConfig = await ensureReportConfig(program);
+
+      expect(reportConfig.admin.equals(provider.wallet.publicKey)).to.be.true;
+      expect(reportConfig.treasury.equals(squatter.publicKey)).to.be.false;
+      expect(reportConfig.bondLamports.toNumber()).to.equal(REPORT_BOND_LAMPORTS);
+    });
+  });
+});
@@ -0,0 +1,266 @@
+/**
+ * 批量记录互动测试
+ * 整批原子执行：任一条目不合法时整批回滚；Match / Chat 条目需指定对方并校验 MatchEdge
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TrustScore } from "../target/types/trust_score";
+import { SocialGraph } from "../target/types/social_graph";
+import { expect } from "chai";
+import {
+  chainTime,
+  createUser,
+  ensureAuthority,
+  ensureScoringConfig,
+  expectError,
+  findPda,
+  registryPda,
+  scoreHistoryPda,
+  trustScorePda,
+} from "./helpers";
+
+// 与 InteractionEntry::MAX_BATCH 保持一致
+const MAX_BATCH = 8;
+
+describe("interactions-batch", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.TrustScore as Program<TrustScore>;
+  const socialGraph = anchor.workspace.SocialGraph as Program<SocialGraph>;
+  const attester = provider.wallet.publicKey;
+
+  let alice: anchor.web3.Keypair;
+  let bob: anchor.web3.Keypair;
+
+  const entry = (
+    owner: anchor.web3.PublicKey,
+    interactionType: Record<string, object> = { helpfulResponse: {} },
+    counterparty: anchor.web3.PublicKey | null = null
+  ) => ({
+    owner,
+    interactionType,
+    qualityScore: 80,
+    counterparty,
+  });
+
+  const pair = (trustScoreOwner: anchor.web3.PublicKey, historyOwner = trustScoreOwner) => [
+    { pubkey: trustScorePda(program, trustScoreOwner), isSigner: false, isWritable: true },
+    { pubkey: scoreHistoryPda(program, historyOwner), isSigner: false, isWritable: true },
+  ];
+
+  const recordBatch = (
+    entries: ReturnType<typeof entry>[],
+    remainingAccounts: anchor.web3.AccountMeta[]
+  ) =>
+    program.methods
+      .recordInteractionsBatch(entries as any)
+      .accounts({ attester })
+      .remainingAccounts(remainingAccounts)
+      .rpc();
+
+  const fetchTrustScore = (owner: anchor.web3.PublicKey) =>
+    program.account.trustScore.fetch(trustScorePda(program, owner));
+
+  before(async () => {
+    await ensureScoringConfig(program);
+    await ensureAuthority(program, "attester");
+    await ensureAuthority(program, "moderator");
+
+    alice = await createUser(program);
+    bob = await createUser(program);
+  });
+
+  it("应该原子记录整批互动", async () => {
+    await recordBatch(
+      [entry(alice.publicKey), entry(bob.publicKey)],
+      [...pair(alice.publicKey), ...pair(bob.publicKey)]
+    );
+
+    for (const user of [alice, bob]) {
+      const trustScore = await fetchTrustScore(user.publicKey);
+      expect(trustScore.totalInteractions).to.equal(1);
+      expect(trustScore.positiveInteractions).to.equal(1);
+    }
+  });
+
+  it("应该拒绝空批次和超过上限的批次", async () => {
+    await expectError(recordBatch([], []), "InvalidBatchSize");
+
+    const oversized = Array.from({ length: MAX_BATCH + 1 }, () => entry(alice.publicKey));
+    await expectError(recordBatch(oversized, []), "InvalidBatchSize");
+  });
+
+  it("应该拒绝批内重复的账户且不写入任何条目", async () => {
+    await expectError(
+      recordBatch(
+        [entry(bob.publicKey), entry(alice.publicKey), entry(alice.publicKey)],
+        [...pair(bob.publicKey), ...pair(alice.publicKey), ...pair(alice.publicKey)]
+      ),
+      "DuplicateBatchEntry"
+    );
+
+    // 排在重复条目之前的 bob 也随整批回滚
+    expect((await fetchTrustScore(bob.publicKey)).totalInteractions).to.equal(1);
+  });
+
+  it("应该拒绝与条目不匹配的账户", async () => {
+    // 传入他人的 TrustScore
+    await expectError(
+      recordBatch([entry(alice.publicKey)], pair(bob.publicKey)),
+      "InvalidTrustScoreAccount"
+    );
+
+    // 传入他人的 ScoreHistory
+    await expectError(
+      recordBatch([entry(alice.publicKey)], pair(alice.publicKey, bob.publicKey)),
+      "InvalidScoreHistoryAccount"
+    );
+
+    // 账户数量与条目不一致
+    await expectError(
+      recordBatch([entry(alice.publicKey), entry(bob.publicKey)], pair(alice.publicKey)),
+      "InvalidRemainingAccounts"
+    );
+  });
+
+  describe("Match / Chat 条目", () => {
+    let carol: anchor.web3.Keypair;
+    let dave: anchor.web3.Keypair;
+    let matchEdge: anchor.web3.PublicKey;
+
+    const interactionPairPda = (owner: anchor.web3.PublicKey, counterparty: anchor.web3.PublicKey) =>
+      findPda(program, [Buffer.from("interaction_pair"), owner.toBuffer(), counterparty.toBuffer()]);
+
+    // [TrustScore, ScoreHistory, MatchEdge, InteractionPair]
+    const matchAccounts = (owner: anchor.web3.PublicKey, counterparty: anchor.web3.PublicKey) => [
+      ...pair(owner),
+      { pubkey: matchEdge, isSigner: false, isWritable: false },
+      { pubkey: interactionPairPda(owner, counterparty), isSigner: false, isWritable: true },
+    ];
+
+    before(async () => {
+      carol = await createUser(program);
+      dave = await createUser(program);
+
+      const [minKey, maxKey] = [carol.publicKey, dave.publicKey].sort((a, b) => a.toBuffer().compare(b.toBuffer()));
+      matchEdge = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("match_edge"), minKey.toBuffer(), maxKey.toBuffer()],
+        socialGraph.programId
+      )[0];
+
+      await socialGraph.methods
+        .createMatchEdge(dave.publicKey)
+        .accountsPartial({
+          matchEdge,
+          userA: carol.publicKey,
+          userATrustScore: trustScorePda(program, carol.publicKey),
+          userBTrustScore: trustScorePda(program, dave.publicKey),
+        })
+        .signers([carol])
+        .rpc();
+    });
+
+    it("应该拒绝未指定对方的 Match 条目和指定了对方的其他条目", async () => {
+      await expectError(
+        recordBatch([entry(carol.publicKey, { match: {} })], pair(carol.publicKey)),
+        "MatchEdgeRequired"
+      );
+      await expectError(
+        recordBatch([entry(carol.publicKey, { helpfulResponse: {} }, dave.publicKey)], pair(carol.publicKey)),
+        "InvalidInteractionType"
+      );
+      await expectError(
+        recordBatch([entry(carol.publicKey, { chat: {} }, carol.publicKey)], pair(carol.publicKey)),
+        "SelfInteraction"
+      );
+    });
+
+    it("对方确认匹配前不能记录", async () => {
+      await expectError(
+        recordBatch(
+          [entry(carol.publicKey, { match: {} }, dave.publicKey)],
+          matchAccounts(carol.publicKey, dave.publicKey)
+        ),
+        "MatchNotAccepted"
+      );
+    });
+
+    it("应该记录双方的 Match / Chat 并计入同一对方的互动窗口", async () => {
+      await socialGraph.methods
+        .acceptMatchEdge()
+        .accountsPartial({
+          matchEdge,
+          acceptor: dave.publicKey,
+          acceptorTrustScore: trustScorePda(program, dave.publicKey),
+        })
+        .signers([dave])
+        .rpc();
+
+      // 缺少 MatchEdge / InteractionPair 时整批回滚
+      await expectError(
+        recordBatch([entry(carol.publicKey, { match: {} }, dave.publicKey)], pair(carol.publicKey)),
+        "InvalidRemainingAccounts"
+      );
+
+      // InteractionPair 必须按 (owner, 对方) 派生
+      await expectError(
+        recordBatch(
+          [entry(carol.publicKey, { match: {} }, dave.publicKey)],
+          [
+            ...pair(carol.publicKey),
+            { pubkey: matchEdge, isSigner: false, isWritable: false },
+            { pubkey: interactionPairPda(dave.publicKey, carol.publicKey), isSigner: false, isWritable: true },
+          ]
+        ),
+        "InvalidInteractionPairAccount"
+      );
+
+      await recordBatch(
+        [
+          entry(carol.publicKey, { match: {} }, dave.publicKey),
+          entry(dave.publicKey, { chat: {} }, carol.publicKey),
+        ],
+        [...matchAccounts(carol.publicKey, dave.publicKey), ...matchAccounts(dave.publicKey, carol.publicKey)]
+      );
+      await recordBatch(
+        [entry(carol.publicKey, { chat: {} }, dave.publicKey)],
+        matchAccounts(carol.publicKey, dave.publicKey)
+      );
+
+      expect((await fetchTrustScore(carol.publicKey)).totalInteractions).to.equal(2);
+      expect((await fetchTrustScore(dave.publicKey)).totalInteractions).to.equal(1);
+
+      const carolPair = await program.account.interactionPair.fetch(interactionPairPda(carol.publicKey, dave.publicKey));
+      expect(carolPair.owner.equals(carol.publicKey)).to.be.true;
+      expect(carolPair.counterparty.equals(dave.publicKey)).to.be.true;
+      expect(carolPair.windowCount).to.equal(2);
+      expect(carolPair.totalCount).to.equal(2);
+
+      const davePair = await program.account.interactionPair.fetch(interactionPairPda(dave.publicKey, carol.publicKey));
+      expect(davePair.totalCount).to.equal(1);
+    });
+  });
+
+  it("应该拒绝包含非正常账户的批次", async () => {
+    const until = (await chainTime(program)) + 3600;
+    await program.methods
+      .setAccountStatus({ suspended: { until: new anchor.BN(until) } })
+      .accountsPartial({
+        trustScore: trustScorePda(program, bob.publicKey),
+        moderatorRegistry: registryPda(program, "moderator"),
+        moderator: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    await expectError(
+      recordBatch(
+        [entry(alice.publicKey), entry(bob.publicKey)],
+        [...pair(alice.publicKey), ...pair(bob.publicKey)]
+      ),
+      "AccountSuspended"
+    );
+    expect((await fetchTrustScore(alice.publicKey)).totalInteractions).to.equal(1);
+  });
+});
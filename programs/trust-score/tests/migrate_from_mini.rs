@@ -0,0 +1,365 @@
+//! migrate_from_mini 测试
+//! mini 程序（anchor 0.27）不在 anchor test 的工作区内，直接调用处理函数；
+//! 非 SBF 目标上 CPI 会直接 panic，处理函数执行到 mini 的 close 即停止，这里只覆盖本程序一侧的新建与合并
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::Discriminator;
+use solana_sysvar::program_stubs::{set_syscall_stubs, SyscallStubs};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use trust_score::{
+    AccountStatus, ErrorCode, MigrateFromMini, MigrateFromMiniBumps, MiniTrustAccountView,
+    ScoreCause, ScoreHistory, ScoringConfig, ScoringParams, SubScores, TrustScore,
+    TRUST_SCORE_MINI_PROGRAM_ID,
+};
+
+const NOW: i64 = 1_700_000_000;
+/// solana-invoke 在非 SBF 目标上的 panic 信息
+const CPI_UNAVAILABLE: &str = "only supported with `target_os = \"solana\"";
+
+/// 非 SBF 目标上 Clock::get 默认不可用，固定为 NOW
+struct FixedClock;
+
+impl SyscallStubs for FixedClock {
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            unix_timestamp: NOW,
+            ..Clock::default()
+        };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        0
+    }
+}
+
+fn init_runtime() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        set_syscall_stubs(Box::new(FixedClock));
+
+        // 到达 CPI 是预期的结束点，不输出 panic 信息
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !info.to_string().contains(CPI_UNAVAILABLE) {
+                default_hook(info);
+            }
+        }));
+    });
+}
+
+fn trust_score(owner: Pubkey, base_score: u16) -> TrustScore {
+    let (_, bump) =
+        Pubkey::find_program_address(&[b"trust_score", owner.as_ref()], &trust_score::ID);
+    TrustScore {
+        owner,
+        base_score,
+        total_interactions: 4,
+        positive_interactions: 3,
+        reports_received: 0,
+        reports_validated: 0,
+        learning_streak: 0,
+        last_active: NOW - 60,
+        bump,
+        attestation_nonce: 0,
+        last_decay_at: NOW - 60,
+        outstanding_vouches: 0,
+        vouches_received: 0,
+        sub_scores: SubScores {
+            social: base_score,
+            learning: base_score,
+            mentoring: base_score,
+            safety: base_score,
+        },
+        created_at: NOW - 86_400,
+        status: AccountStatus::Active,
+        last_appeal_at: 0,
+        last_settled_epoch: 0,
+        pending_reports: 0,
+    }
+}
+
+fn score_history(owner: Pubkey) -> ScoreHistory {
+    let (_, bump) =
+        Pubkey::find_program_address(&[b"score_history", owner.as_ref()], &trust_score::ID);
+    let mut history = zeroed::<ScoreHistory>(ScoreHistory::LEN);
+    history.owner = owner;
+    history.bump = bump;
+    history.push(NOW - 86_400, TrustScore::INITIAL_SCORE, ScoreCause::Initialized);
+    history
+}
+
+/// init_if_needed 新建的账户：只写入 discriminator，其余字段为零
+fn zeroed<T: AccountDeserialize + Discriminator>(len: usize) -> T {
+    let mut data = T::DISCRIMINATOR.to_vec();
+    data.resize(len, 0);
+    T::try_deserialize(&mut data.as_slice()).unwrap()
+}
+
+/// 序列化后的账户（地址、owner 程序、数据）
+struct Fixture {
+    key: Pubkey,
+    owner: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+    executable: bool,
+}
+
+impl Fixture {
+    fn new<T: AccountSerialize>(owner: Pubkey, account: &T) -> Self {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        Self::raw(owner, data)
+    }
+
+    fn raw(owner: Pubkey, data: Vec<u8>) -> Self {
+        Self {
+            key: Pubkey::new_unique(),
+            owner,
+            lamports: 1_000_000,
+            data,
+            executable: false,
+        }
+    }
+
+    fn mini(owner: Pubkey, score: u64) -> Self {
+        let mut data = MiniTrustAccountView::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(owner.as_ref());
+        data.extend_from_slice(&score.to_le_bytes());
+        data.extend_from_slice(&7u64.to_le_bytes());
+        Self::raw(TRUST_SCORE_MINI_PROGRAM_ID, data)
+    }
+
+    fn program(key: Pubkey) -> Self {
+        Self {
+            key,
+            owner: Pubkey::default(),
+            lamports: 1,
+            data: Vec::new(),
+            executable: true,
+        }
+    }
+
+    fn info(&mut self, is_signer: bool) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.key,
+            is_signer,
+            true,
+            &mut self.lamports,
+            &mut self.data,
+            &self.owner,
+            self.executable,
+            0,
+        )
+    }
+}
+
+/// migrate_from_mini 所需的全部账户
+struct Migration {
+    user: Pubkey,
+    mini: Fixture,
+    trust_score: Fixture,
+    score_history: Fixture,
+    user_wallet: Fixture,
+    migrator: Fixture,
+    scoring_config: Fixture,
+    payer: Fixture,
+    mini_program: Fixture,
+    system_program: Fixture,
+}
+
+impl Migration {
+    /// trust_score / history 为 None 时与 init_if_needed 新建的账户一致（全零）
+    fn new(mini_score: u64, trust_score: Option<TrustScore>, history: Option<ScoreHistory>) -> Self {
+        let user = trust_score.as_ref().map_or_else(Pubkey::new_unique, |score| score.owner);
+        let (_, config_bump) = Pubkey::find_program_address(&[b"scoring_config"], &trust_score::ID);
+        let config = ScoringConfig {
+            admin: Pubkey::new_unique(),
+            version: 3,
+            params: ScoringParams::default(),
+            bump: config_bump,
+        };
+
+        Self {
+            user,
+            mini: Fixture::mini(user, mini_score),
+            trust_score: Fixture::new(
+                trust_score::ID,
+                &trust_score.unwrap_or_else(|| zeroed(TrustScore::LEN)),
+            ),
+            score_history: Fixture::new(
+                trust_score::ID,
+                &history.unwrap_or_else(|| zeroed(ScoreHistory::LEN)),
+            ),
+            user_wallet: Fixture {
+                key: user,
+                ..Fixture::raw(Pubkey::default(), Vec::new())
+            },
+            migrator: Fixture {
+                key: Pubkey::find_program_address(&[b"mini_migrator"], &trust_score::ID).0,
+                ..Fixture::raw(Pubkey::default(), Vec::new())
+            },
+            scoring_config: Fixture::new(trust_score::ID, &config),
+            payer: Fixture::raw(Pubkey::default(), Vec::new()),
+            mini_program: Fixture::program(TRUST_SCORE_MINI_PROGRAM_ID),
+            system_program: Fixture::program(anchor_lang::system_program::ID),
+        }
+    }
+
+    fn bumps(&self) -> MigrateFromMiniBumps {
+        let seed = |prefix: &[u8]| {
+            Pubkey::find_program_address(&[prefix, self.user.as_ref()], &trust_score::ID).1
+        };
+        MigrateFromMiniBumps {
+            mini_trust_account: Pubkey::find_program_address(
+                &[b"trust_score", self.user.as_ref()],
+                &TRUST_SCORE_MINI_PROGRAM_ID,
+            )
+            .1,
+            trust_score: seed(b"trust_score"),
+            score_history: seed(b"score_history"),
+            mini_migrator: Pubkey::find_program_address(&[b"mini_migrator"], &trust_score::ID).1,
+        }
+    }
+
+    /// 执行迁移，返回处理函数是否执行到了 mini 的 close CPI
+    fn run(&mut self) -> Result<bool> {
+        init_runtime();
+        let bumps = self.bumps();
+        let mini = self.mini.info(false);
+        let trust_score = self.trust_score.info(false);
+        let score_history = self.score_history.info(false);
+        let user = self.user_wallet.info(false);
+        let migrator = self.migrator.info(false);
+        let scoring_config = self.scoring_config.info(false);
+        let payer = self.payer.info(true);
+        let mini_program = self.mini_program.info(false);
+        let system_program = self.system_program.info(false);
+
+        let mut accounts = MigrateFromMini {
+            mini_trust_account: UncheckedAccount::try_from(&mini),
+            trust_score: Box::new(Account::try_from(&trust_score)?),
+            score_history: Box::new(Account::try_from(&score_history)?),
+            user: UncheckedAccount::try_from(&user),
+            mini_migrator: UncheckedAccount::try_from(&migrator),
+            scoring_config: Box::new(Account::try_from(&scoring_config)?),
+            payer: Signer::try_from(&payer)?,
+            mini_program: UncheckedAccount::try_from(&mini_program),
+            system_program: Program::try_from(&system_program)?,
+        };
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            trust_score::trust_score::migrate_from_mini(Context::new(
+                &trust_score::ID,
+                &mut accounts,
+                &[],
+                bumps,
+            ))
+        }));
+        let reached_close = match result {
+            Ok(result) => {
+                result?;
+                false
+            }
+            Err(panic) => {
+                let message = panic.downcast_ref::<&str>().copied().unwrap_or_default();
+                assert!(message.contains(CPI_UNAVAILABLE), "unexpected panic: {message}");
+                true
+            }
+        };
+
+        accounts.exit(&trust_score::ID)?;
+        Ok(reached_close)
+    }
+
+    fn trust_score(&self) -> TrustScore {
+        TrustScore::try_deserialize(&mut self.trust_score.data.as_slice()).unwrap()
+    }
+
+    fn score_history(&self) -> ScoreHistory {
+        ScoreHistory::try_deserialize(&mut self.score_history.data.as_slice()).unwrap()
+    }
+}
+
+/// 最近一条分数快照
+fn latest(history: &ScoreHistory) -> (u16, ScoreCause) {
+    let index = (history.head as usize + ScoreHistory::CAPACITY - 1) % ScoreHistory::CAPACITY;
+    let snapshot = &history.entries[index];
+    (snapshot.base_score, snapshot.cause)
+}
+
+#[test]
+fn migrate_creates_trust_score_and_history() {
+    let mut migration = Migration::new(60, None, None);
+    assert!(migration.run().unwrap());
+
+    let bumps = migration.bumps();
+    let score = migration.trust_score();
+    assert_eq!(score.owner, migration.user);
+    assert_eq!(score.base_score, 60);
+    assert_eq!(score.bump, bumps.trust_score);
+    assert_eq!(score.created_at, NOW);
+    assert_eq!(score.sub_scores.safety, 60);
+
+    let history = migration.score_history();
+    assert_eq!(history.owner, migration.user);
+    assert_eq!(history.bump, bumps.score_history);
+    assert_eq!(history.len, 1);
+    assert_eq!(latest(&history), (60, ScoreCause::Migrated));
+}
+
+#[test]
+fn migrate_caps_mini_scores_at_initial_score() {
+    let mut migration = Migration::new(5_000, None, None);
+    assert!(migration.run().unwrap());
+
+    assert_eq!(migration.trust_score().base_score, TrustScore::INITIAL_SCORE);
+}
+
+#[test]
+fn migrate_merges_mini_deficit_into_existing_trust_score() {
+    let existing = trust_score(Pubkey::new_unique(), 300);
+    let history = score_history(existing.owner);
+    let mut migration = Migration::new(60, Some(existing.clone()), Some(history.clone()));
+    assert!(migration.run().unwrap());
+
+    // 300 - (100 - 60)，其余字段保持不变
+    let score = migration.trust_score();
+    assert_eq!(score.base_score, 260);
+    assert_eq!(score.sub_scores.social, 260);
+    assert_eq!(score.total_interactions, existing.total_interactions);
+    assert_eq!(score.created_at, existing.created_at);
+
+    let merged = migration.score_history();
+    assert_eq!(merged.bump, history.bump);
+    assert_eq!(merged.len, history.len + 1);
+    assert_eq!(latest(&merged), (260, ScoreCause::Migrated));
+}
+
+#[test]
+fn migrate_initializes_missing_history_when_merging() {
+    let existing = trust_score(Pubkey::new_unique(), 300);
+    let mut migration = Migration::new(100, Some(existing), None);
+    assert!(migration.run().unwrap());
+
+    let history = migration.score_history();
+    assert_eq!(history.owner, migration.user);
+    assert_eq!(history.bump, migration.bumps().score_history);
+    assert_eq!(latest(&history), (300, ScoreCause::Migrated));
+}
+
+#[test]
+fn migrate_rejects_foreign_or_malformed_mini_accounts() {
+    let mut foreign = Migration::new(60, None, None);
+    foreign.mini = Fixture::mini(Pubkey::new_unique(), 60);
+    assert_eq!(
+        foreign.run().unwrap_err(),
+        ErrorCode::InvalidMiniAccount.into()
+    );
+
+    let mut malformed = Migration::new(60, None, None);
+    malformed.mini.data[0] ^= 1;
+    assert_eq!(
+        malformed.run().unwrap_err(),
+        ErrorCode::InvalidMiniAccount.into()
+    );
+    assert_eq!(malformed.trust_score().owner, Pubkey::default());
+}
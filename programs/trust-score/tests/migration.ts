@@ -0,0 +1,195 @@
+/**
+ * 钱包轮换测试
+ * 信誉、分数历史与担保关系一并迁移到新钱包，旧账户关闭
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TrustScore } from "../target/types/trust_score";
+import { expect } from "chai";
+import {
+  accountExists,
+  createUser,
+  ensureReportConfig,
+  ensureScoringConfig,
+  expectError,
+  findPda,
+  fundedKeypair,
+  overrideScoringParams,
+  scoreHistoryPda,
+  trustScorePda,
+} from "./helpers";
+
+describe("migration", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.TrustScore as Program<TrustScore>;
+
+  let originalParams: Awaited<ReturnType<typeof overrideScoringParams>>;
+
+  const vouchPda = (voucher: anchor.web3.PublicKey, vouchee: anchor.web3.PublicKey) =>
+    findPda(program, [Buffer.from("vouch"), voucher.toBuffer(), vouchee.toBuffer()]);
+
+  const fetchTrustScore = (owner: anchor.web3.PublicKey) =>
+    program.account.trustScore.fetch(trustScorePda(program, owner));
+
+  const vouchFor = (voucher: anchor.web3.Keypair, vouchee: anchor.web3.PublicKey) =>
+    program.methods
+      .vouchFor()
+      .accountsPartial({ voucher: voucher.publicKey, vouchee })
+      .signers([voucher])
+      .rpc();
+
+  // [旧 Vouch, 新 Vouch PDA]
+  const rekeyPair = (
+    [voucher, vouchee]: anchor.web3.PublicKey[],
+    [newVoucher, newVouchee]: anchor.web3.PublicKey[]
+  ) => [
+    { pubkey: vouchPda(voucher, vouchee), isSigner: false, isWritable: true },
+    { pubkey: vouchPda(newVoucher, newVouchee), isSigner: false, isWritable: true },
+  ];
+
+  const migrate = (
+    oldOwner: anchor.web3.Keypair,
+    newOwner: anchor.web3.Keypair,
+    vouches: anchor.web3.AccountMeta[] = []
+  ) =>
+    program.methods
+      .migrateTrustScore()
+      .accountsPartial({ oldOwner: oldOwner.publicKey, newOwner: newOwner.publicKey })
+      .remainingAccounts(vouches)
+      .signers([oldOwner, newOwner])
+      .rpc();
+
+  before(async () => {
+    await ensureScoringConfig(program);
+    await ensureReportConfig(program);
+
+    // 新用户分数低于默认担保门槛，测试期间放开
+    originalParams = await overrideScoringParams(program, { minVoucherScore: 0 });
+  });
+
+  after(async () => {
+    await overrideScoringParams(program, originalParams);
+  });
+
+  it("应该把信誉迁移到新钱包并关闭旧账户", async () => {
+    const oldOwner = await createUser(program);
+    const newOwner = await fundedKeypair(program);
+    const before = await fetchTrustScore(oldOwner.publicKey);
+
+    await migrate(oldOwner, newOwner);
+
+    expect(await accountExists(program, trustScorePda(program, oldOwner.publicKey))).to.be.false;
+    expect(await accountExists(program, scoreHistoryPda(program, oldOwner.publicKey))).to.be.false;
+
+    const after = await fetchTrustScore(newOwner.publicKey);
+    expect(after.owner.equals(newOwner.publicKey)).to.be.true;
+    expect(after.baseScore).to.equal(before.baseScore);
+    expect(after.createdAt.toNumber()).to.equal(before.createdAt.toNumber());
+
+    const history = await program.account.scoreHistory.fetch(scoreHistoryPda(program, newOwner.publicKey));
+    expect(history.owner.equals(newOwner.publicKey)).to.be.true;
+  });
+
+  describe("担保关系", () => {
+    let oldOwner: anchor.web3.Keypair;
+    let newOwner: anchor.web3.Keypair;
+    let voucher: anchor.web3.Keypair;
+    let vouchee: anchor.web3.Keypair;
+
+    before(async () => {
+      oldOwner = await createUser(program);
+      newOwner = await fundedKeypair(program);
+      voucher = await createUser(program);
+      vouchee = await createUser(program);
+
+      // voucher -> oldOwner -> vouchee
+      await vouchFor(voucher, oldOwner.publicKey);
+      await vouchFor(oldOwner, vouchee.publicKey);
+    });
+
+    it("应该拒绝未传入全部担保的迁移", async () => {
+      await expectError(migrate(oldOwner, newOwner), "IncompleteVouchAccounts");
+      await expectError(
+        migrate(
+          oldOwner,
+          newOwner,
+          rekeyPair([voucher.publicKey, oldOwner.publicKey], [voucher.publicKey, newOwner.publicKey])
+        ),
+        "IncompleteVouchAccounts"
+      );
+
+      // 新 Vouch 地址必须按新钱包派生
+      await expectError(
+        migrate(oldOwner, newOwner, [
+          ...rekeyPair([voucher.publicKey, oldOwner.publicKey], [voucher.publicKey, oldOwner.publicKey]),
+          ...rekeyPair([oldOwner.publicKey, vouchee.publicKey], [newOwner.publicKey, vouchee.publicKey]),
+        ]),
+        "InvalidVouchAccount"
+      );
+    });
+
+    it("应该把收到和发出的担保迁移到新钱包", async () => {
+      const before = await fetchTrustScore(oldOwner.publicKey);
+      const received = await program.account.vouch.fetch(vouchPda(voucher.publicKey, oldOwner.publicKey));
+
+      await migrate(oldOwner, newOwner, [
+        ...rekeyPair([voucher.publicKey, oldOwner.publicKey], [voucher.publicKey, newOwner.publicKey]),
+        ...rekeyPair([oldOwner.publicKey, vouchee.publicKey], [newOwner.publicKey, vouchee.publicKey]),
+      ]);
+
+      for (const [from, to] of [
+        [voucher.publicKey, oldOwner.publicKey],
+        [oldOwner.publicKey, vouchee.publicKey],
+      ]) {
+        expect(await accountExists(program, vouchPda(from, to))).to.be.false;
+      }
+
+      const rekeyed = await program.account.vouch.fetch(vouchPda(voucher.publicKey, newOwner.publicKey));
+      expect(rekeyed.voucher.equals(voucher.publicKey)).to.be.true;
+      expect(rekeyed.vouchee.equals(newOwner.publicKey)).to.be.true;
+      expect(rekeyed.weight).to.equal(received.weight);
+      expect(rekeyed.createdAt.toNumber()).to.equal(received.createdAt.toNumber());
+
+      const given = await program.account.vouch.fetch(vouchPda(newOwner.publicKey, vouchee.publicKey));
+      expect(given.voucher.equals(newOwner.publicKey)).to.be.true;
+
+      const after = await fetchTrustScore(newOwner.publicKey);
+      expect(after.vouchesReceived).to.equal(before.vouchesReceived);
+      expect(after.outstandingVouches).to.equal(before.outstandingVouches);
+    });
+
+    it("迁移后的担保可以由双方正常撤销", async () => {
+      await program.methods
+        .revokeVouch()
+        .accountsPartial({ vouch: vouchPda(voucher.publicKey, newOwner.publicKey), voucher: voucher.publicKey })
+        .signers([voucher])
+        .rpc();
+      await program.methods
+        .revokeVouch()
+        .accountsPartial({ vouch: vouchPda(newOwner.publicKey, vouchee.publicKey), voucher: newOwner.publicKey })
+        .signers([newOwner])
+        .rpc();
+
+      const migrated = await fetchTrustScore(newOwner.publicKey);
+      expect(migrated.vouchesReceived).to.equal(0);
+      expect(migrated.outstandingVouches).to.equal(0);
+      expect((await fetchTrustScore(vouchee.publicKey)).vouchesReceived).to.equal(0);
+      expect((await fetchTrustScore(voucher.publicKey)).outstandingVouches).to.equal(0);
+    });
+  });
+
+  it("有待审核的举报时不能迁移", async () => {
+    const oldOwner = await createUser(program);
+    const reporter = await createUser(program);
+    await program.methods
+      .reportUser("spam", Array(32).fill(0))
+      .accountsPartial({ reporter: reporter.publicKey, reportedUser: oldOwner.publicKey })
+      .signers([reporter])
+      .rpc();
+
+    await expectError(migrate(oldOwner, await fundedKeypair(program)), "PendingReports");
+  });
+});
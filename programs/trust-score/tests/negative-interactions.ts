@@ -0,0 +1,116 @@
+/**
+ * 负面行为测试
+ * 爽约、垃圾信息、骚扰、诈骗只能由已注册的证明方记录，被记录用户无需签名
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TrustScore } from "../target/types/trust_score";
+import { expect } from "chai";
+import {
+  createUser,
+  ensureAuthority,
+  ensureScoringConfig,
+  expectError,
+  findPda,
+  fundedKeypair,
+  trustScorePda,
+} from "./helpers";
+
+const NEGATIVE_TYPES = ["noShow", "spam", "harassment", "scam"] as const;
+
+describe("negative-interactions", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.TrustScore as Program<TrustScore>;
+  const attester = provider.wallet.publicKey;
+
+  const fetchTrustScore = (owner: anchor.web3.PublicKey) =>
+    program.account.trustScore.fetch(trustScorePda(program, owner));
+
+  const recordNegative = (
+    user: anchor.web3.PublicKey,
+    interactionType: Record<string, object>,
+    signer: anchor.web3.Keypair | null = null
+  ) => {
+    const builder = program.methods
+      .recordNegativeInteraction(interactionType as any)
+      .accountsPartial({
+        user,
+        counterparty: null,
+        attester: signer ? signer.publicKey : attester,
+        instructionsSysvar: null,
+      });
+    return signer ? builder.signers([signer]).rpc() : builder.rpc();
+  };
+
+  before(async () => {
+    await ensureScoringConfig(program);
+    await ensureAuthority(program, "attester");
+  });
+
+  for (const type of NEGATIVE_TYPES) {
+    it(`证明方记录 ${type} 应该扣分`, async () => {
+      const { params } = await program.account.scoringConfig.fetch(
+        findPda(program, [Buffer.from("scoring_config")])
+      );
+      const severity = params[`${type}Severity` as keyof typeof params] as number;
+      const user = await createUser(program);
+      const before = await fetchTrustScore(user.publicKey);
+
+      await recordNegative(user.publicKey, { [type]: {} });
+
+      // 按严重程度直接扣分，不参与各项加成
+      const after = await fetchTrustScore(user.publicKey);
+      expect(after.baseScore).to.equal(Math.max(before.baseScore - severity, 0));
+      expect(after.totalInteractions).to.equal(before.totalInteractions + 1);
+      expect(after.positiveInteractions).to.equal(before.positiveInteractions);
+      expect(after.attestationNonce.toNumber()).to.equal(before.attestationNonce.toNumber() + 1);
+    });
+  }
+
+  it("应该拒绝通过负面接口记录正面互动", async () => {
+    const user = await createUser(program);
+    await expectError(recordNegative(user.publicKey, { helpfulResponse: {} }), "InvalidInteractionType");
+  });
+
+  it("未注册的证明方不能记录负面行为", async () => {
+    const user = await createUser(program);
+    const outsider = await fundedKeypair(program, 1);
+
+    await expectError(recordNegative(user.publicKey, { spam: {} }, outsider), "UnauthorizedAttester");
+  });
+
+  it("缺少证明时应该拒绝，即使未开启强制模式", async () => {
+    const user = await createUser(program);
+
+    await expectError(
+      program.methods
+        .recordNegativeInteraction({ scam: {} })
+        .accountsPartial({ user: user.publicKey, counterparty: null, attester: null, instructionsSysvar: null })
+        .rpc(),
+      "AttestationRequired"
+    );
+  });
+
+  it("用户不能通过 record_interaction 记录负面行为", async () => {
+    const user = await createUser(program);
+    const counterparty = await createUser(program);
+
+    await expectError(
+      program.methods
+        .recordInteraction({ spam: {} }, 0)
+        .accountsPartial({
+          owner: user.publicKey,
+          counterparty: counterparty.publicKey,
+          matchEdge: null,
+          attester: null,
+          instructionsSysvar: null,
+        })
+        .signers([user])
+        .rpc(),
+      "NegativeInteractionRequiresAttester"
+    );
+  });
+});
@@ -0,0 +1,113 @@
+/**
+ * 举报保证金测试
+ * 举报时托管保证金；驳回时罚没至金库；审核通过后须等申诉期结束才能取回
+ * 申诉期满后的取回在 report_bond.rs 中以固定时钟覆盖
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TrustScore } from "../target/types/trust_score";
+import { expect } from "chai";
+import {
+  bondVaultPda,
+  createUser,
+  ensureAuthority,
+  ensureReportConfig,
+  ensureScoringConfig,
+  expectError,
+  registryPda,
+  reportPda,
+} from "./helpers";
+
+describe("report-bond", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.TrustScore as Program<TrustScore>;
+  const connection = provider.connection;
+  const bondVault = bondVaultPda(program);
+
+  let treasury: anchor.web3.PublicKey;
+  let bondLamports: number;
+
+  const balance = (address: anchor.web3.PublicKey) => connection.getBalance(address);
+
+  // 新建举报人与被举报人并提交举报
+  const fileReport = async () => {
+    const reporter = await createUser(program);
+    const reported = await createUser(program);
+
+    await program.methods
+      .reportUser("spam", Array(32).fill(0))
+      .accountsPartial({ reporter: reporter.publicKey, reportedUser: reported.publicKey })
+      .signers([reporter])
+      .rpc();
+
+    return { reporter, report: reportPda(program, reporter.publicKey, reported.publicKey) };
+  };
+
+  const resolveAccounts = (report: anchor.web3.PublicKey) => ({
+    report,
+    moderatorRegistry: registryPda(program, "moderator"),
+    moderator: provider.wallet.publicKey,
+    treasury,
+  });
+
+  const withdrawBond = (reporter: anchor.web3.Keypair, report: anchor.web3.PublicKey) =>
+    program.methods
+      .withdrawReportBond()
+      .accountsPartial({ report, reporter: reporter.publicKey })
+      .signers([reporter])
+      .rpc();
+
+  before(async () => {
+    await ensureScoringConfig(program);
+    await ensureAuthority(program, "moderator");
+    const reportConfig = await ensureReportConfig(program);
+    treasury = reportConfig.treasury;
+    bondLamports = reportConfig.bondLamports.toNumber();
+    expect(bondLamports).to.be.greaterThan(0);
+  });
+
+  it("举报时应该把保证金托管到金库账户", async () => {
+    const vaultBefore = await balance(bondVault);
+    const { report } = await fileReport();
+
+    expect(await balance(bondVault)).to.equal(vaultBefore + bondLamports);
+
+    const stored = await program.account.report.fetch(report);
+    expect(stored.bondLamports.toNumber()).to.equal(bondLamports);
+    expect(stored.bondSettled).to.be.false;
+  });
+
+  it("驳回的举报应该罚没保证金至金库", async () => {
+    const { reporter, report } = await fileReport();
+    const vaultBefore = await balance(bondVault);
+    const treasuryBefore = await balance(treasury);
+
+    await program.methods.dismissReport().accountsPartial(resolveAccounts(report)).rpc();
+
+    expect(await balance(bondVault)).to.equal(vaultBefore - bondLamports);
+    expect(await balance(treasury)).to.equal(treasuryBefore + bondLamports);
+    expect((await program.account.report.fetch(report)).bondSettled).to.be.true;
+
+    // 已罚没的保证金不能再由举报人取回
+    await expectError(withdrawBond(reporter, report), "BondNotWithdrawable");
+  });
+
+  it("待审核的举报不能取回保证金", async () => {
+    const { reporter, report } = await fileReport();
+    await expectError(withdrawBond(reporter, report), "BondNotWithdrawable");
+  });
+
+  it("审核通过后申诉期内不能取回保证金", async () => {
+    const { reporter, report } = await fileReport();
+    await program.methods.validateReport().accountsPartial(resolveAccounts(report)).rpc();
+    const vaultBefore = await balance(bondVault);
+
+    await expectError(withdrawBond(reporter, report), "AppealWindowOpen");
+
+    expect(await balance(bondVault)).to.equal(vaultBefore);
+    expect((await program.account.report.fetch(report)).bondSettled).to.be.false;
+  });
+});
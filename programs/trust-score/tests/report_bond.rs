@@ -0,0 +1,196 @@
+//! 举报保证金取回测试
+//! 申诉期（7 天）无法在本地验证器上等待，直接以固定时钟调用 withdraw_report_bond
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::clock::Clock;
+use solana_sysvar::program_stubs::{set_syscall_stubs, SyscallStubs};
+use std::sync::Once;
+use trust_score::{
+    BondVault, ErrorCode, Report, ReportStatus, WithdrawReportBond, WithdrawReportBondBumps,
+};
+
+const NOW: i64 = 1_700_000_000;
+const BOND: u64 = 10_000_000;
+const VAULT_RENT: u64 = 1_000_000;
+
+/// 非 SBF 目标上 Clock::get 默认不可用，固定为 NOW
+struct FixedClock;
+
+impl SyscallStubs for FixedClock {
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            unix_timestamp: NOW,
+            ..Clock::default()
+        };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        0
+    }
+}
+
+fn init_clock() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        set_syscall_stubs(Box::new(FixedClock));
+    });
+}
+
+fn report(reporter: Pubkey, status: ReportStatus, resolved_at: Option<i64>) -> Report {
+    Report {
+        reporter,
+        reported_user: Pubkey::new_unique(),
+        reason: "spam".to_string(),
+        evidence_hash: [0; 32],
+        status,
+        created_at: 0,
+        resolved_at,
+        resolved_by: resolved_at.map(|_| Pubkey::new_unique()),
+        bond_lamports: BOND,
+        bump: 255,
+        penalty_applied: 0,
+        voucher_penalties: Vec::new(),
+        bond_settled: false,
+    }
+}
+
+/// 序列化后的账户（地址、owner 程序、余额、数据）
+struct Fixture {
+    key: Pubkey,
+    owner: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+    is_signer: bool,
+}
+
+impl Fixture {
+    fn new<T: AccountSerialize>(owner: Pubkey, lamports: u64, account: &T) -> Self {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        Self {
+            key: Pubkey::new_unique(),
+            owner,
+            lamports,
+            data,
+            is_signer: false,
+        }
+    }
+
+    fn wallet(key: Pubkey, lamports: u64) -> Self {
+        Self {
+            key,
+            owner: anchor_lang::system_program::ID,
+            lamports,
+            data: Vec::new(),
+            is_signer: true,
+        }
+    }
+
+    fn info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.key,
+            self.is_signer,
+            true,
+            &mut self.lamports,
+            &mut self.data,
+            &self.owner,
+            false,
+            0,
+        )
+    }
+}
+
+/// 报告、金库、举报人三个账户
+struct Bond {
+    report: Fixture,
+    vault: Fixture,
+    reporter: Fixture,
+}
+
+impl Bond {
+    fn new(status: ReportStatus, resolved_at: Option<i64>) -> Self {
+        let reporter = Pubkey::new_unique();
+        Self {
+            report: Fixture::new(trust_score::ID, 1, &report(reporter, status, resolved_at)),
+            vault: Fixture::new(trust_score::ID, VAULT_RENT + BOND, &BondVault { bump: 255 }),
+            reporter: Fixture::wallet(reporter, 0),
+        }
+    }
+
+    fn withdraw(&mut self) -> Result<()> {
+        init_clock();
+        let report = self.report.info();
+        let vault = self.vault.info();
+        let reporter = self.reporter.info();
+
+        let mut accounts = WithdrawReportBond {
+            report: Account::try_from(&report)?,
+            bond_vault: Account::try_from(&vault)?,
+            reporter: Signer::try_from(&reporter)?,
+        };
+        trust_score::trust_score::withdraw_report_bond(Context::new(
+            &trust_score::ID,
+            &mut accounts,
+            &[],
+            WithdrawReportBondBumps::default(),
+        ))?;
+        accounts.exit(&trust_score::ID)
+    }
+
+    fn report(&self) -> Report {
+        Report::try_deserialize(&mut self.report.data.as_slice()).unwrap()
+    }
+}
+
+#[test]
+fn withdraw_releases_bond_after_appeal_window() {
+    let mut bond = Bond::new(
+        ReportStatus::Validated,
+        Some(NOW - Report::APPEAL_WINDOW_SECONDS),
+    );
+
+    bond.withdraw().unwrap();
+
+    assert_eq!(bond.reporter.lamports, BOND);
+    assert_eq!(bond.vault.lamports, VAULT_RENT);
+    assert!(bond.report().bond_settled);
+
+    // 已结算的保证金不能重复取回
+    assert_eq!(
+        bond.withdraw().unwrap_err(),
+        ErrorCode::BondNotWithdrawable.into()
+    );
+    assert_eq!(bond.reporter.lamports, BOND);
+}
+
+#[test]
+fn withdraw_rejects_open_appeal_window() {
+    let mut bond = Bond::new(
+        ReportStatus::Validated,
+        Some(NOW - Report::APPEAL_WINDOW_SECONDS + 1),
+    );
+
+    assert_eq!(
+        bond.withdraw().unwrap_err(),
+        ErrorCode::AppealWindowOpen.into()
+    );
+    assert_eq!(bond.vault.lamports, VAULT_RENT + BOND);
+    assert!(!bond.report().bond_settled);
+}
+
+#[test]
+fn withdraw_rejects_unvalidated_reports() {
+    let resolved_at = Some(NOW - Report::APPEAL_WINDOW_SECONDS);
+
+    for (status, resolved_at) in [
+        (ReportStatus::Pending, None),
+        (ReportStatus::Dismissed, resolved_at),
+        (ReportStatus::Appealed, resolved_at),
+        (ReportStatus::Overturned, resolved_at),
+    ] {
+        let mut bond = Bond::new(status, resolved_at);
+        assert_eq!(
+            bond.withdraw().unwrap_err(),
+            ErrorCode::BondNotWithdrawable.into()
+        );
+        assert_eq!(bond.vault.lamports, VAULT_RENT + BOND);
+    }
+}
@@ -0,0 +1,295 @@
+/**
+ * 举报与审核测试
+ * 举报在审核前不扣分；审核通过时扣减被举报人分数，并对其全部担保人连带扣分
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TrustScore } from "../target/types/trust_score";
+import { expect } from "chai";
+import {
+  chainTime,
+  createUser,
+  ensureAuthority,
+  ensureReportConfig,
+  ensureScoringConfig,
+  expectError,
+  expectFailure,
+  findPda,
+  fundedKeypair,
+  overrideScoringParams,
+  registryPda,
+  reportPda,
+  scoreHistoryPda,
+  trustScorePda,
+} from "./helpers";
+
+describe("reports", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.TrustScore as Program<TrustScore>;
+  const moderator = provider.wallet.publicKey;
+
+  let treasury: anchor.web3.PublicKey;
+  let originalParams: Awaited<ReturnType<typeof overrideScoringParams>>;
+
+  const fetchTrustScore = (owner: anchor.web3.PublicKey) =>
+    program.account.trustScore.fetch(trustScorePda(program, owner));
+
+  const reportUser = (reporter: anchor.web3.Keypair, reportedUser: anchor.web3.PublicKey, reason = "spam") =>
+    program.methods
+      .reportUser(reason, Array(32).fill(7))
+      .accountsPartial({ reporter: reporter.publicKey, reportedUser })
+      .signers([reporter])
+      .rpc();
+
+  const vouchFor = (voucher: anchor.web3.Keypair, vouchee: anchor.web3.PublicKey) =>
+    program.methods
+      .vouchFor()
+      .accountsPartial({ voucher: voucher.publicKey, vouchee })
+      .signers([voucher])
+      .rpc();
+
+  const vouchGroup = (voucher: anchor.web3.PublicKey, vouchee: anchor.web3.PublicKey) => [
+    {
+      pubkey: findPda(program, [Buffer.from("vouch"), voucher.toBuffer(), vouchee.toBuffer()]),
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: trustScorePda(program, voucher), isSigner: false, isWritable: true },
+    { pubkey: scoreHistoryPda(program, voucher), isSigner: false, isWritable: true },
+  ];
+
+  const resolveAccounts = (report: anchor.web3.PublicKey, signer = moderator) => ({
+    report,
+    moderatorRegistry: registryPda(program, "moderator"),
+    moderator: signer,
+    treasury,
+  });
+
+  const validateReport = (report: anchor.web3.PublicKey, vouches: anchor.web3.AccountMeta[] = []) =>
+    program.methods.validateReport().accountsPartial(resolveAccounts(report)).remainingAccounts(vouches).rpc();
+
+  before(async () => {
+    await ensureScoringConfig(program);
+    await ensureAuthority(program, "moderator");
+    ({ treasury } = await ensureReportConfig(program));
+
+    // 新用户分数低于默认担保门槛，测试期间放开
+    originalParams = await overrideScoringParams(program, { minVoucherScore: 0 });
+  });
+
+  after(async () => {
+    await overrideScoringParams(program, originalParams);
+  });
+
+  it("提交举报不扣分，只计入待审核", async () => {
+    const reporter = await createUser(program);
+    const reported = await createUser(program);
+    const before = await fetchTrustScore(reported.publicKey);
+
+    await reportUser(reporter, reported.publicKey);
+
+    const report = await program.account.report.fetch(reportPda(program, reporter.publicKey, reported.publicKey));
+    expect(report.status).to.deep.equal({ pending: {} });
+    expect(report.reporter.equals(reporter.publicKey)).to.be.true;
+
+    const after = await fetchTrustScore(reported.publicKey);
+    expect(after.baseScore).to.equal(before.baseScore);
+    expect(after.reportsReceived).to.equal(before.reportsReceived + 1);
+    expect(after.pendingReports).to.equal(before.pendingReports + 1);
+    expect(after.reportsValidated).to.equal(0);
+  });
+
+  it("应该拒绝自我举报、过长理由和重复举报", async () => {
+    const reporter = await createUser(program);
+    const reported = await createUser(program);
+
+    await expectError(reportUser(reporter, reporter.publicKey), "SelfReport");
+    await expectError(reportUser(reporter, reported.publicKey, "x".repeat(201)), "ReasonTooLong");
+
+    // 每个 (举报人, 被举报人) 只有一个举报 PDA
+    await reportUser(reporter, reported.publicKey);
+    await expectFailure(reportUser(reporter, reported.publicKey));
+  });
+
+  it("暂停中的账户不能举报", async () => {
+    const reporter = await createUser(program);
+    const reported = await createUser(program);
+    const until = (await chainTime(program)) + 3600;
+
+    await program.methods
+      .setAccountStatus({ suspended: { until: new anchor.BN(until) } })
+      .accountsPartial({
+        trustScore: trustScorePda(program, reporter.publicKey),
+        moderatorRegistry: registryPda(program, "moderator"),
+        moderator,
+      })
+      .rpc();
+
+    await expectError(reportUser(reporter, reported.publicKey), "AccountSuspended");
+  });
+
+  describe("审核", () => {
+    let reporter: anchor.web3.Keypair;
+    let reported: anchor.web3.Keypair;
+    let voucherA: anchor.web3.Keypair;
+    let voucherB: anchor.web3.Keypair;
+    let report: anchor.web3.PublicKey;
+
+    before(async () => {
+      reporter = await createUser(program);
+      reported = await createUser(program);
+      voucherA = await createUser(program);
+      voucherB = await createUser(program);
+
+      await vouchFor(voucherA, reported.publicKey);
+      await vouchFor(voucherB, reported.publicKey);
+      await reportUser(reporter, reported.publicKey);
+      report = reportPda(program, reporter.publicKey, reported.publicKey);
+    });
+
+    it("非版主不能审核举报", async () => {
+      const outsider = await fundedKeypair(program, 1);
+
+      await expectError(
+        program.methods
+          .validateReport()
+          .accountsPartial(resolveAccounts(report, outsider.publicKey))
+          .signers([outsider])
+          .rpc(),
+        "UnauthorizedModerator"
+      );
+    });
+
+    it("应该拒绝省略担保人的审核", async () => {
+      await expectError(validateReport(report), "IncompleteVouchAccounts");
+      await expectError(
+        validateReport(report, vouchGroup(voucherA.publicKey, reported.publicKey)),
+        "IncompleteVouchAccounts"
+      );
+
+      // 同一担保传两次也不能凑数
+      await expectError(
+        validateReport(report, [
+          ...vouchGroup(voucherA.publicKey, reported.publicKey),
+          ...vouchGroup(voucherA.publicKey, reported.publicKey),
+        ]),
+        "InvalidVouchAccount"
+      );
+    });
+
+    it("审核通过时扣减被举报人分数并对担保人连带扣分", async () => {
+      const { params } = await program.account.scoringConfig.fetch(
+        findPda(program, [Buffer.from("scoring_config")])
+      );
+      const reportedBefore = await fetchTrustScore(reported.publicKey);
+      const vouchersBefore = await Promise.all([voucherA, voucherB].map((v) => fetchTrustScore(v.publicKey)));
+
+      await validateReport(report, [
+        ...vouchGroup(voucherA.publicKey, reported.publicKey),
+        ...vouchGroup(voucherB.publicKey, reported.publicKey),
+      ]);
+
+      const resolved = await program.account.report.fetch(report);
+      expect(resolved.status).to.deep.equal({ validated: {} });
+      expect(resolved.resolvedBy!.equals(moderator)).to.be.true;
+
+      const reportedAfter = await fetchTrustScore(reported.publicKey);
+      expect(reportedAfter.reportsValidated).to.equal(1);
+      expect(reportedAfter.pendingReports).to.equal(reportedBefore.pendingReports - 1);
+      expect(resolved.penaltyApplied).to.be.greaterThan(0);
+      expect(reportedAfter.baseScore).to.equal(reportedBefore.baseScore - resolved.penaltyApplied);
+
+      expect(resolved.voucherPenalties).to.have.length(2);
+      for (const [i, voucher] of [voucherA, voucherB].entries()) {
+        const after = await fetchTrustScore(voucher.publicKey);
+        const penalty = resolved.voucherPenalties[i];
+        expect(penalty.voucher.equals(voucher.publicKey)).to.be.true;
+        expect(penalty.points).to.equal(Math.min(params.voucherPenalty, vouchersBefore[i].baseScore));
+        expect(after.baseScore).to.equal(vouchersBefore[i].baseScore - penalty.points);
+      }
+    });
+
+    it("已处理的举报不能再次审核或驳回", async () => {
+      await expectError(
+        validateReport(report, [
+          ...vouchGroup(voucherA.publicKey, reported.publicKey),
+          ...vouchGroup(voucherB.publicKey, reported.publicKey),
+        ]),
+        "ReportAlreadyResolved"
+      );
+      await expectError(
+        program.methods.dismissReport().accountsPartial(resolveAccounts(report)).rpc(),
+        "ReportAlreadyResolved"
+      );
+    });
+  });
+
+  it("担保双方迁移钱包后仍然可以审核并连带扣分", async () => {
+    const vouchPda = (voucher: anchor.web3.PublicKey, vouchee: anchor.web3.PublicKey) =>
+      findPda(program, [Buffer.from("vouch"), voucher.toBuffer(), vouchee.toBuffer()]);
+    const migrate = (
+      oldOwner: anchor.web3.Keypair,
+      newOwner: anchor.web3.Keypair,
+      [voucher, vouchee]: anchor.web3.PublicKey[],
+      [newVoucher, newVouchee]: anchor.web3.PublicKey[]
+    ) =>
+      program.methods
+        .migrateTrustScore()
+        .accountsPartial({ oldOwner: oldOwner.publicKey, newOwner: newOwner.publicKey })
+        .remainingAccounts([
+          { pubkey: vouchPda(voucher, vouchee), isSigner: false, isWritable: true },
+          { pubkey: vouchPda(newVoucher, newVouchee), isSigner: false, isWritable: true },
+        ])
+        .signers([oldOwner, newOwner])
+        .rpc();
+
+    const reporter = await createUser(program);
+    const reported = await createUser(program);
+    const voucher = await createUser(program);
+    await vouchFor(voucher, reported.publicKey);
+
+    const newVoucher = await fundedKeypair(program);
+    await migrate(voucher, newVoucher, [voucher.publicKey, reported.publicKey], [
+      newVoucher.publicKey,
+      reported.publicKey,
+    ]);
+    const newReported = await fundedKeypair(program);
+    await migrate(reported, newReported, [newVoucher.publicKey, reported.publicKey], [
+      newVoucher.publicKey,
+      newReported.publicKey,
+    ]);
+
+    await reportUser(reporter, newReported.publicKey);
+    const report = reportPda(program, reporter.publicKey, newReported.publicKey);
+    const voucherBefore = await fetchTrustScore(newVoucher.publicKey);
+
+    await validateReport(report, vouchGroup(newVoucher.publicKey, newReported.publicKey));
+
+    const resolved = await program.account.report.fetch(report);
+    expect(resolved.status).to.deep.equal({ validated: {} });
+    expect(resolved.voucherPenalties).to.have.length(1);
+    expect(resolved.voucherPenalties[0].voucher.equals(newVoucher.publicKey)).to.be.true;
+    expect((await fetchTrustScore(newVoucher.publicKey)).baseScore).to.equal(
+      voucherBefore.baseScore - resolved.voucherPenalties[0].points
+    );
+  });
+
+  it("驳回的举报不扣分", async () => {
+    const reporter = await createUser(program);
+    const reported = await createUser(program);
+    await reportUser(reporter, reported.publicKey);
+    const report = reportPda(program, reporter.publicKey, reported.publicKey);
+    const before = await fetchTrustScore(reported.publicKey);
+
+    await program.methods.dismissReport().accountsPartial(resolveAccounts(report)).rpc();
+
+    expect((await program.account.report.fetch(report)).status).to.deep.equal({ dismissed: {} });
+    const after = await fetchTrustScore(reported.publicKey);
+    expect(after.baseScore).to.equal(before.baseScore);
+    expect(after.pendingReports).to.equal(before.pendingReports - 1);
+    expect(after.reportsValidated).to.equal(0);
+  });
+});
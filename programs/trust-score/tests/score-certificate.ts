@@ -0,0 +1,62 @@
+/**
+ * 信誉证书测试
+ * 签发后账户被暂停，证书随之失效
+ */
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { TrustScore } from "../target/types/trust_score";
+import { expect } from "chai";
+import {
+  chainTime,
+  createUser,
+  ensureAuthority,
+  ensureScoringConfig,
+  expectError,
+  registryPda,
+  trustScorePda,
+} from "./helpers";
+
+describe("score-certificate", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.TrustScore as Program<TrustScore>;
+
+  let user: anchor.web3.Keypair;
+
+  const verifyCertificate = (minScore: number) =>
+    program.methods.verifyCertificate(minScore).accounts({ user: user.publicKey });
+
+  before(async () => {
+    await ensureScoringConfig(program);
+    await ensureAuthority(program, "moderator");
+
+    user = await createUser(program);
+    await program.methods
+      .issueScoreCertificate(new anchor.BN(1000))
+      .accounts({ owner: user.publicKey })
+      .signers([user])
+      .rpc();
+  });
+
+  it("应该通过有效证书的校验", async () => {
+    const score = await verifyCertificate(0).view();
+    expect(score).to.be.greaterThan(0);
+    await expectError(verifyCertificate(score + 1).rpc(), "ScoreBelowMinimum");
+  });
+
+  it("账户被暂停后证书应该失效", async () => {
+    const until = (await chainTime(program)) + 3600;
+    await program.methods
+      .setAccountStatus({ suspended: { until: new anchor.BN(until) } })
+      .accountsPartial({
+        trustScore: trustScorePda(program, user.publicKey),
+        moderatorRegistry: registryPda(program, "moderator"),
+        moderator: provider.wallet.publicKey,
+      })
+      .rpc();
+
+    await expectError(verifyCertificate(0).rpc(), "AccountSuspended");
+  });
+});
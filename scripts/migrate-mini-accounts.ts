@@ -0,0 +1,170 @@
+/**
+ * 将 trust-score-mini 的 TrustAccount 批量迁移到完整版 TrustScore
+ * 枚举 mini 程序下所有账户，逐个调用 trust-score 的 migrate_from_mini（已有 TrustScore 的用户合并）
+ *
+ * 用法: npx ts-node scripts/migrate-mini-accounts.ts [--dry-run]
+ * 环境变量: SOLANA_RPC_URL、PAYER_KEYPAIR（默认 ~/.config/solana/devnet.json）
+ */
+
+import {
+  Connection,
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  Transaction,
+  TransactionInstruction,
+  sendAndConfirmTransaction,
+} from '@solana/web3.js';
+import { createHash } from 'crypto';
+import * as fs from 'fs';
+import * as path from 'path';
+
+const connection = new Connection(
+  process.env.SOLANA_RPC_URL || 'https://api.devnet.solana.com',
+  { commitment: 'confirmed', confirmTransactionInitialTimeout: 60000 }
+);
+
+// 与 programs/trust-score-mini 和 programs/trust-score 的 declare_id 保持一致
+const MINI_PROGRAM_ID = new PublicKey('3FWDkwEPfVVZmxXS4f3pDaJpg4qf7GL5ir89DtXSwAjR');
+const TRUST_SCORE_PROGRAM_ID = new PublicKey('8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR2');
+
+// discriminator(8) + owner(32) + score(8) + total_interactions(8)
+const MINI_ACCOUNT_SIZE = 8 + 32 + 8 + 8;
+
+/**
+ * Anchor discriminator: sha256("<namespace>:<name>")[..8]
+ */
+function discriminator(name: string): Buffer {
+  return createHash('sha256').update(name).digest().subarray(0, 8);
+}
+
+function findPda(seeds: Buffer[], programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(seeds, programId)[0];
+}
+
+interface MiniAccount {
+  address: PublicKey;
+  owner: PublicKey;
+  score: bigint;
+  totalInteractions: bigint;
+}
+
+/**
+ * 枚举 mini 程序下的所有 TrustAccount
+ */
+async function fetchMiniAccounts(): Promise<MiniAccount[]> {
+  const accounts = await connection.getProgramAccounts(MINI_PROGRAM_ID, {
+    filters: [
+      { dataSize: MINI_ACCOUNT_SIZE },
+      { memcmp: { offset: 0, bytes: discriminator('account:TrustAccount').toString('base64'), encoding: 'base64' } },
+    ],
+  });
+
+  return accounts.map(({ pubkey, account }) => ({
+    address: pubkey,
+    owner: new PublicKey(account.data.subarray(8, 40)),
+    score: account.data.readBigUInt64LE(40),
+    totalInteractions: account.data.readBigUInt64LE(48),
+  }));
+}
+
+/**
+ * 构造 migrate_from_mini 指令（账户顺序与 MigrateFromMini 上下文一致）
+ */
+function migrateInstruction(payer: PublicKey, mini: MiniAccount): TransactionInstruction {
+  const user = mini.owner;
+
+  return new TransactionInstruction({
+    programId: TRUST_SCORE_PROGRAM_ID,
+    keys: [
+      { pubkey: mini.address, isSigner: false, isWritable: true },
+      { pubkey: findPda([Buffer.from('trust_score'), user.toBuffer()], TRUST_SCORE_PROGRAM_ID), isSigner: false, isWritable: true },
+      { pubkey: findPda([Buffer.from('score_history'), user.toBuffer()], TRUST_SCORE_PROGRAM_ID), isSigner: false, isWritable: true },
+      { pubkey: user, isSigner: false, isWritable: true },
+      { pubkey: findPda([Buffer.from('mini_migrator')], TRUST_SCORE_PROGRAM_ID), isSigner: false, isWritable: false },
+      { pubkey: findPda([Buffer.from('scoring_config')], TRUST_SCORE_PROGRAM_ID), isSigner: false, isWritable: false },
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: MINI_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    data: discriminator('global:migrate_from_mini'),
+  });
+}
+
+/**
+ * 主函数
+ */
+async function main() {
+  const dryRun = process.argv.includes('--dry-run');
+
+  const payerKeypairPath =
+    process.env.PAYER_KEYPAIR || path.join(process.env.HOME || '', '.config/solana/devnet.json');
+  if (!fs.existsSync(payerKeypairPath)) {
+    console.error('❌ 找不到钱包文件:', payerKeypairPath);
+    process.exit(1);
+  }
+  const payer = Keypair.fromSecretKey(
+    new Uint8Array(JSON.parse(fs.readFileSync(payerKeypairPath, 'utf-8')))
+  );
+
+  console.log('💼 付款人地址:', payer.publicKey.toString());
+
+  const miniAccounts = await fetchMiniAccounts();
+  console.log(`🔍 找到 ${miniAccounts.length} 个 mini 账户\n`);
+
+  let migrated = 0;
+  let merged = 0;
+  let failed = 0;
+
+  for (const mini of miniAccounts) {
+    const trustScore = findPda([Buffer.from('trust_score'), mini.owner.toBuffer()], TRUST_SCORE_PROGRAM_ID);
+    console.log(`📝 ${mini.owner.toString()} (score ${mini.score}, interactions ${mini.totalInteractions})`);
+
+    // 已存在完整版账户的用户由程序合并 mini 扣分，不新建账户
+    const existing = (await connection.getAccountInfo(trustScore)) !== null;
+    if (existing) {
+      console.log('🔀 已有 TrustScore 账户，合并');
+    }
+
+    if (dryRun) {
+      if (existing) {
+        merged++;
+      } else {
+        migrated++;
+      }
+      continue;
+    }
+
+    try {
+      const signature = await sendAndConfirmTransaction(
+        connection,
+        new Transaction().add(migrateInstruction(payer.publicKey, mini)),
+        [payer],
+        { commitment: 'confirmed' }
+      );
+      console.log('✅ 迁移成功:', signature);
+      if (existing) {
+        merged++;
+      } else {
+        migrated++;
+      }
+    } catch (error) {
+      console.error('❌ 迁移失败:', error);
+      failed++;
+    }
+
+    // 等待一下避免速率限制
+    await new Promise(resolve => setTimeout(resolve, 500));
+  }
+
+  console.log(`\n${dryRun ? '🧪 演练' : '✅ 完成'}: 新建 ${migrated}，合并 ${merged}，失败 ${failed}`);
+  if (failed > 0) {
+    process.exit(1);
+  }
+}
+
+// 运行
+main().catch((error) => {
+  console.error('❌ 迁移中止:', error);
+  process.exit(1);
+});